@@ -29,6 +29,7 @@ export async function markComplete(
   })
     .addOperation(contract.call(
       'mark_complete',
+      nativeToScVal(keypair.publicKey(), { type: 'address' }),
       nativeToScVal(milestoneIndex, { type: 'u32' }),
       nativeToScVal(taskUrl,        { type: 'string' }),
     ))
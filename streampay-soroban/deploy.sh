@@ -96,7 +96,23 @@ echo "  --backend \$(stellar keys address backend) \\"
 echo "  --token  <USDC_CONTRACT_ID> \\"
 echo "  --total_amount 100_0000000 \\"
 echo "  --milestones '[{...}]' \\"
-echo "  --dispute_window_secs 259200"
+echo "  --dispute_window_secs 259200 \\"
+echo "  --config '{...}' \\"
+echo "  --metadata '{...}'"
+echo ""
+echo "   Full milestone / config / metadata JSON: see README.md"
+echo "   (\"Initialize an Escrow\"). The escrow starts as a Draft."
+echo ""
+echo "── Then: developer accepts, client funds ────────────────────────────────"
+echo ""
+echo "HASH=\$(stellar contract invoke --id $CONTRACT_ID --network $NETWORK \\"
+echo "  -- get_terms_hash | tr -d '\"')"
+echo ""
+echo "stellar contract invoke --id $CONTRACT_ID --source developer --network $NETWORK \\"
+echo "  -- accept_terms --terms_hash \$HASH"
+echo ""
+echo "stellar contract invoke --id $CONTRACT_ID --source client --network $NETWORK \\"
+echo "  -- fund --terms_hash \$HASH"
 echo ""
 echo "── Testnet USDC ─────────────────────────────────────────────────────────"
 echo "   Deploy a mock token or use the Stellar lab token:"
@@ -0,0 +1,222 @@
+// lib.rs — KeeperRegistry Soroban Contract
+//
+// A shared index of upcoming escrow deadlines.
+//
+// StreamEscrow instances configured with a registry push an entry when a
+// milestone starts a countdown (mark_complete) and clear it when the
+// milestone leaves review (release, dispute, cancel). Keepers then make a
+// single paginated due_before() query instead of polling every escrow's
+// time_until_auto_release() per milestone.
+//
+// Entries are keyed by (escrow, milestone_index) and can only be written
+// by the escrow contract they describe — its address must authorize the
+// call, which Soroban grants automatically for direct contract calls.
+// The registry only says *where to look*: the escrow itself still enforces
+// every deadline, so a stale or bogus entry costs a keeper a failed call,
+// never funds.
+//
+// Storage layout: each escrow's deadlines live in one entry of their own
+// (at most MAX_ESCROW_DEADLINES), and escrows with anything registered are
+// listed in a dense directory keepers page through. Nothing is shared
+// between escrows, so there is no global cap anyone can fill: registering
+// junk under your own address only makes keepers page a little further,
+// and never pushes out another escrow's deadlines.
+
+#![no_std]
+
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Map, Vec};
+
+#[cfg(test)]
+mod tests;
+
+/// Max escrows scanned by one due_before() page.
+pub const MAX_PAGE_SIZE: u32 = 25;
+
+/// Max deadlines one escrow can hold (StreamEscrow's milestone limit).
+pub const MAX_ESCROW_DEADLINES: u32 = 10;
+
+// Persistent entries are bumped to ~30 days whenever they drop below ~15.
+const TTL_THRESHOLD: u32 = 259_200;
+const TTL_EXTEND_TO: u32 = 518_400;
+
+// ─── Types ────────────────────────────────────────────────────────────────────
+
+/// One actionable deadline: after `due_at`, `escrow` can be poked for
+/// `milestone_index` (e.g. auto_release).
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeadlineEntry {
+    pub escrow: Address,
+    pub milestone_index: u32,
+    pub due_at: u64,
+}
+
+#[contracttype]
+pub enum DataKey {
+    /// escrow → (milestone_index → due_at)
+    Deadlines(Address),
+    /// Directory slot → escrow with at least one deadline
+    Escrow(u32),
+    /// escrow → its directory slot
+    Slot(Address),
+    /// Number of listed escrows (instance storage)
+    EscrowCount,
+    /// Number of tracked deadlines (instance storage)
+    Count,
+}
+
+// ─── Contract ─────────────────────────────────────────────────────────────────
+
+#[contract]
+pub struct KeeperRegistry;
+
+#[contractimpl]
+impl KeeperRegistry {
+
+    /// Add or update the deadline for one of the caller's milestones.
+    ///
+    /// `escrow` must authorize — escrows call this with their own address.
+    /// Panics if the escrow already holds MAX_ESCROW_DEADLINES other entries.
+    pub fn register(env: Env, escrow: Address, milestone_index: u32, due_at: u64) {
+        escrow.require_auth();
+
+        let mut deadlines = Self::deadlines(&env, &escrow);
+        if !deadlines.contains_key(milestone_index) {
+            if deadlines.len() >= MAX_ESCROW_DEADLINES {
+                panic!("too many deadlines for this escrow");
+            }
+            if deadlines.is_empty() {
+                Self::list(&env, &escrow);
+            }
+            Self::set_count(&env, Self::count(env.clone()) + 1);
+        }
+
+        deadlines.set(milestone_index, due_at);
+        Self::set_deadlines(&env, &escrow, &deadlines);
+    }
+
+    /// Remove the deadline for one of the caller's milestones.
+    ///
+    /// No-op if nothing is registered, so escrows can clear unconditionally.
+    pub fn clear(env: Env, escrow: Address, milestone_index: u32) {
+        escrow.require_auth();
+
+        let mut deadlines = Self::deadlines(&env, &escrow);
+        if deadlines.remove(milestone_index).is_none() {
+            return;
+        }
+        if deadlines.is_empty() {
+            Self::unlist(&env, &escrow);
+        }
+        Self::set_deadlines(&env, &escrow, &deadlines);
+        Self::set_count(&env, Self::count(env.clone()).saturating_sub(1));
+    }
+
+    /// Deadlines at or before `timestamp` among the escrows in directory
+    /// slots `start..start + limit` (limit capped at MAX_PAGE_SIZE).
+    ///
+    /// Keepers walk the whole directory by advancing `start` by `limit`
+    /// until it reaches escrow_count(). An escrow whose last deadline is
+    /// cleared mid-walk hands its slot to the last one listed, which the
+    /// walk may then miss until the next round.
+    pub fn due_before(env: Env, timestamp: u64, start: u32, limit: u32) -> Vec<DeadlineEntry> {
+        let limit = limit.min(MAX_PAGE_SIZE);
+        let end   = start.saturating_add(limit).min(Self::escrow_count(env.clone()));
+        let mut page = Vec::new(&env);
+
+        for slot in start..end {
+            let Some(escrow) = env.storage().persistent().get::<_, Address>(&DataKey::Escrow(slot)) else {
+                continue;
+            };
+            for (milestone_index, due_at) in Self::deadlines(&env, &escrow).iter() {
+                if due_at <= timestamp {
+                    page.push_back(DeadlineEntry { escrow: escrow.clone(), milestone_index, due_at });
+                }
+            }
+        }
+
+        page
+    }
+
+    /// Deadline registered for a milestone, if any.
+    pub fn get_deadline(env: Env, escrow: Address, milestone_index: u32) -> Option<u64> {
+        Self::deadlines(&env, &escrow).get(milestone_index)
+    }
+
+    /// Total number of tracked deadlines, due or not.
+    pub fn count(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::Count).unwrap_or(0)
+    }
+
+    /// Number of escrows with at least one deadline — the directory size.
+    pub fn escrow_count(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::EscrowCount).unwrap_or(0)
+    }
+
+    // ─── Internal ─────────────────────────────────────────────────────────────
+
+    fn deadlines(env: &Env, escrow: &Address) -> Map<u32, u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Deadlines(escrow.clone()))
+            .unwrap_or_else(|| Map::new(env))
+    }
+
+    fn set_deadlines(env: &Env, escrow: &Address, deadlines: &Map<u32, u64>) {
+        let key = DataKey::Deadlines(escrow.clone());
+        if deadlines.is_empty() {
+            env.storage().persistent().remove(&key);
+        } else {
+            env.storage().persistent().set(&key, deadlines);
+            env.storage().persistent().extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+            Self::extend_listing(env, escrow);
+        }
+    }
+
+    fn set_count(env: &Env, count: u32) {
+        env.storage().instance().set(&DataKey::Count, &count);
+        env.storage().instance().extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    fn set_escrow_count(env: &Env, count: u32) {
+        env.storage().instance().set(&DataKey::EscrowCount, &count);
+        env.storage().instance().extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    /// Append `escrow` to the directory.
+    fn list(env: &Env, escrow: &Address) {
+        let slot = Self::escrow_count(env.clone());
+        env.storage().persistent().set(&DataKey::Escrow(slot), escrow);
+        env.storage().persistent().set(&DataKey::Slot(escrow.clone()), &slot);
+        Self::set_escrow_count(env, slot + 1);
+    }
+
+    /// Drop `escrow` from the directory, moving the last escrow into its slot.
+    fn unlist(env: &Env, escrow: &Address) {
+        let storage = env.storage().persistent();
+        let Some(slot) = storage.get::<_, u32>(&DataKey::Slot(escrow.clone())) else {
+            return;
+        };
+        let last = Self::escrow_count(env.clone()).saturating_sub(1);
+
+        if slot != last {
+            if let Some(moved) = storage.get::<_, Address>(&DataKey::Escrow(last)) {
+                storage.set(&DataKey::Escrow(slot), &moved);
+                storage.set(&DataKey::Slot(moved.clone()), &slot);
+                Self::extend_listing(env, &moved);
+            }
+        }
+        storage.remove(&DataKey::Escrow(last));
+        storage.remove(&DataKey::Slot(escrow.clone()));
+        Self::set_escrow_count(env, last);
+    }
+
+    fn extend_listing(env: &Env, escrow: &Address) {
+        let storage = env.storage().persistent();
+        let slot_key = DataKey::Slot(escrow.clone());
+        if let Some(slot) = storage.get::<_, u32>(&slot_key) {
+            storage.extend_ttl(&slot_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+            storage.extend_ttl(&DataKey::Escrow(slot), TTL_THRESHOLD, TTL_EXTEND_TO);
+        }
+    }
+}
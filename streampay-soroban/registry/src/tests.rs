@@ -0,0 +1,137 @@
+#![cfg(test)]
+
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+use crate::{DeadlineEntry, KeeperRegistry, KeeperRegistryClient, MAX_ESCROW_DEADLINES, MAX_PAGE_SIZE};
+
+fn setup() -> (Env, KeeperRegistryClient<'static>) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register(KeeperRegistry, ());
+    let client      = KeeperRegistryClient::new(&env, &contract_id);
+
+    (env, client)
+}
+
+#[test]
+fn test_register_and_due_before() {
+    let (env, r) = setup();
+    let escrow_a = Address::generate(&env);
+    let escrow_b = Address::generate(&env);
+
+    r.register(&escrow_a, &0, &100);
+    r.register(&escrow_a, &1, &500);
+    r.register(&escrow_b, &2, &200);
+
+    let due = r.due_before(&200, &0, &10);
+    assert_eq!(due.len(), 2);
+    assert_eq!(due.get(0).unwrap(), DeadlineEntry { escrow: escrow_a.clone(), milestone_index: 0, due_at: 100 });
+    assert_eq!(due.get(1).unwrap(), DeadlineEntry { escrow: escrow_b, milestone_index: 2, due_at: 200 });
+    assert_eq!(r.count(), 3);
+    assert_eq!(r.escrow_count(), 2);
+}
+
+#[test]
+fn test_register_updates_existing_entry() {
+    let (env, r) = setup();
+    let escrow = Address::generate(&env);
+
+    r.register(&escrow, &0, &100);
+    r.register(&escrow, &0, &900);
+
+    assert_eq!(r.count(), 1);
+    assert_eq!(r.get_deadline(&escrow, &0), Some(900));
+    assert_eq!(r.due_before(&500, &0, &10).len(), 0);
+}
+
+#[test]
+fn test_clear_removes_entry() {
+    let (env, r) = setup();
+    let escrow = Address::generate(&env);
+
+    r.register(&escrow, &0, &100);
+    r.clear(&escrow, &0);
+    r.clear(&escrow, &0);
+
+    assert_eq!(r.count(), 0);
+    assert_eq!(r.escrow_count(), 0);
+    assert_eq!(r.get_deadline(&escrow, &0), None);
+}
+
+#[test]
+fn test_due_before_pages_through_escrows() {
+    let (env, r) = setup();
+    let escrows: [Address; 5] = core::array::from_fn(|_| Address::generate(&env));
+
+    for (i, escrow) in escrows.iter().enumerate() {
+        r.register(escrow, &0, &(100 + i as u64));
+    }
+
+    let first  = r.due_before(&1000, &0, &2);
+    let second = r.due_before(&1000, &2, &2);
+    let last   = r.due_before(&1000, &4, &2);
+
+    assert_eq!(first.get(0).unwrap().escrow, escrows[0]);
+    assert_eq!(second.get(0).unwrap().escrow, escrows[2]);
+    assert_eq!(last.len(), 1);
+    assert_eq!(last.get(0).unwrap().escrow, escrows[4]);
+    assert_eq!(r.due_before(&1000, &0, &1000).len(), 5.min(MAX_PAGE_SIZE));
+}
+
+#[test]
+fn test_clear_moves_last_escrow_into_gap() {
+    let (env, r) = setup();
+    let a = Address::generate(&env);
+    let b = Address::generate(&env);
+    let c = Address::generate(&env);
+
+    r.register(&a, &0, &100);
+    r.register(&b, &0, &100);
+    r.register(&c, &0, &100);
+    r.clear(&a, &0);
+
+    assert_eq!(r.escrow_count(), 2);
+    let due = r.due_before(&100, &0, &10);
+    assert_eq!(due.get(0).unwrap().escrow, c);
+    assert_eq!(due.get(1).unwrap().escrow, b);
+}
+
+#[test]
+fn test_spam_cannot_crowd_out_other_escrows() {
+    let (env, r) = setup();
+    let escrow = Address::generate(&env);
+
+    // Someone registers a pile of junk deadlines in the same second
+    for _ in 0..2 * MAX_PAGE_SIZE {
+        r.register(&Address::generate(&env), &0, &100);
+    }
+    r.register(&escrow, &0, &100);
+    assert_eq!(r.get_deadline(&escrow, &0), Some(100));
+
+    // …and it is still found by walking the directory
+    assert!(r.due_before(&100, &0, &MAX_PAGE_SIZE).iter().all(|e| e.escrow != escrow));
+    let mut found = false;
+    let mut start = 0;
+    while start < r.escrow_count() {
+        found |= r.due_before(&100, &start, &MAX_PAGE_SIZE).iter().any(|e| e.escrow == escrow);
+        start += MAX_PAGE_SIZE;
+    }
+    assert!(found);
+}
+
+#[test]
+fn test_escrow_deadlines_are_capped() {
+    let (env, r) = setup();
+    let escrow = Address::generate(&env);
+
+    for i in 0..MAX_ESCROW_DEADLINES {
+        r.register(&escrow, &i, &100);
+    }
+    assert!(r.try_register(&escrow, &MAX_ESCROW_DEADLINES, &200).is_err());
+
+    // Moving an existing entry still works, and other escrows are unaffected
+    r.register(&escrow, &0, &300);
+    assert_eq!(r.count(), MAX_ESCROW_DEADLINES);
+    assert!(r.try_register(&Address::generate(&env), &0, &100).is_ok());
+}
@@ -3,6 +3,9 @@
 // In Soroban, errors are u32 codes returned in the Result.
 // Keep them grouped and documented — clients use these to show
 // meaningful messages to users.
+//
+// #[contracterror] enums are capped at 50 variants, so prefer reusing a
+// code whose meaning fits over adding a new one.
 
 use soroban_sdk::contracterror;
 
@@ -19,27 +22,69 @@ pub enum Error {
     NoMilestones            = 11,
     TooManyMilestones       = 12,  // Max 10 milestones per contract
     InvalidMilestoneBps     = 13,  // BPS must sum to exactly 10_000
+    InvalidKeeperBps        = 14,  // keeper_bps above MAX_KEEPER_BPS
+    InvalidMilestoneAmount  = 15,  // Needs bps xor a fixed amount, and amounts must fit total
+    InvalidConfig           = 16,  // advance, retention, kill fee, late policy or expiry out of range
+    InvalidMetadata         = 17,  // external_id empty or longer than MAX_EXTERNAL_ID_LEN
 
     // ─── Escrow state ─────────────────────────────────────────────────────────
     EscrowCompleted         = 20,  // All milestones resolved — contract is done
     EscrowCancelled         = 21,  // Contract was cancelled
+    EscrowInWarranty        = 22,  // All milestones done — only retention is left
+    EscrowExpired           = 23,  // expire() already ran
+    EscrowInDraft           = 24,  // Terms not accepted and funded yet
+    NotADraft               = 25,  // accept_terms / counter_propose / fund after activation
+    CancelAlreadyRequested  = 26,  // request_cancel() twice, or cancel() during notice
+    EscrowWithdrawn         = 27,  // Developer withdrew from the engagement
+    EscrowTerminated        = 28,  // Closed by mutual termination
 
     // ─── Milestone state ──────────────────────────────────────────────────────
     MilestoneNotFound           = 30,
-    MilestoneAlreadyCompleted   = 31,  // mark_complete() called twice
+    MilestoneAlreadyCompleted   = 31,  // mark_complete() called twice, or milestone already delivered/settled
     MilestoneNotPendingRelease  = 32,  // approve/auto_release called on wrong state
     MilestoneNotCompleted       = 33,  // completed_at timestamp missing
     MilestoneNotDisputed        = 34,  // resolve_dispute called on non-disputed milestone
+    NoTriggerMatch              = 35,  // complete_by_trigger matched no Pending milestone
+    AmbiguousTrigger            = 36,  // complete_by_trigger matched more than one milestone
+    ChangeRequestLimitReached   = 37,  // request_changes() called MAX_CHANGE_REQUESTS times already
+    MilestoneNotPending         = 38,  // change order touches a started milestone, or a new one isn't fresh
 
     // ─── Dispute ──────────────────────────────────────────────────────────────
     NoDisputeWindow         = 40,  // dispute_window is 0 — manual approval only
     DisputeWindowOpen       = 41,  // auto_release called before window expires
     DisputeWindowClosed     = 42,  // dispute() called after window expired
+    ApprovalDeadlineOpen    = 43,  // manual mode — approval deadline not reached yet
+    EscalationNotAvailable  = 44,  // escalate() needs manual mode + ApprovalTimeout::Escalate
+    ReviewWindowClosed      = 45,  // request_changes() after the window / approval deadline
 
     // ─── Release ──────────────────────────────────────────────────────────────
     ManualApprovalRequired  = 50,  // dispute_window is 0 — must use approve()
     CannotCancelWithPendingMilestones = 51,
 
+    // ─── Proposals ────────────────────────────────────────────────────────────
+    NothingPending          = 55,  // accept / withdraw / resolve with nothing proposed or claimed
+    StaleChangeOrder        = 56,  // terms / milestones changed since the order or termination was proposed
+
+    // ─── Authorization ────────────────────────────────────────────────────────
+    NotAParty               = 60,  // Address is neither the client nor the developer (or not a live backend signer)
+
+    // ─── Funding ──────────────────────────────────────────────────────────────
+    MilestoneNotFunded      = 70,  // just-in-time mode — fund before mark_complete()
+    MilestoneAlreadyFunded  = 71,
+
+    // ─── Retention / warranty ─────────────────────────────────────────────────
+    NotInWarranty           = 80,  // no retention held, or the warranty period is over
+    WarrantyClaimOpen       = 81,  // a claim is already open / blocks release
+
+    // ─── Deadlines ────────────────────────────────────────────────────────────
+    DeadlineNotReached      = 90,  // expire / release_retention / reclaim called too early
+    ReclaimNotAvailable     = 91,  // needs LatePolicy::Reclaim and a due_at on the milestone
+
+    // ─── Terms ────────────────────────────────────────────────────────────────
+    TermsHashMismatch       = 100,  // hash doesn't match the terms currently proposed
+    ProposalExpired         = 101,
+    TermsNotAccepted        = 102,  // fund() / finalize_replacement() before the (new) developer accepted
+
     // ─── Math ─────────────────────────────────────────────────────────────────
     Overflow                = 99,
 }
@@ -5,13 +5,26 @@
 //
 // Event structure: env.events().publish((topic1, topic2, ...), data)
 // Topics are used for filtering. Data is the payload.
+//
+// Every event's second topic is the escrow's external id (see
+// EscrowMetadata), so indexers can join events to backend records directly.
+
+use soroban_sdk::{Address, Bytes, BytesN, Env, String, Vec, symbol_short};
 
-use soroban_sdk::{Address, Env, String, symbol_short};
+use crate::storage::Storage;
+use crate::types::{Milestone, Party};
 
 pub struct Events;
 
 impl Events {
 
+    /// Backend id of this escrow — empty until metadata is stored.
+    fn external_id(env: &Env) -> Bytes {
+        Storage::get_metadata(env)
+            .map(|m| m.external_id)
+            .unwrap_or_else(|_| Bytes::new(env))
+    }
+
     pub fn initialized(
         env: &Env,
         client: &Address,
@@ -20,11 +33,46 @@ impl Events {
         milestone_count: u32,
     ) {
         env.events().publish(
-            (symbol_short!("init"),),
+            (symbol_short!("init"), Self::external_id(env)),
             (client, developer, total_amount, milestone_count),
         );
     }
 
+    pub fn milestone_funded(env: &Env, milestone_index: u32, amount: i128) {
+        env.events().publish(
+            (symbol_short!("funded"), Self::external_id(env), milestone_index),
+            amount,
+        );
+    }
+
+    pub fn terms_proposed(env: &Env, proposer: &Party, terms_hash: &BytesN<32>, expires_at: u64) {
+        env.events().publish(
+            (symbol_short!("propose"), Self::external_id(env)),
+            (proposer.clone(), terms_hash.clone(), expires_at),
+        );
+    }
+
+    pub fn terms_accepted(env: &Env, developer: &Address, terms_hash: &BytesN<32>) {
+        env.events().publish(
+            (symbol_short!("accept"), Self::external_id(env)),
+            (developer, terms_hash.clone()),
+        );
+    }
+
+    pub fn activated(env: &Env, client: &Address, deposit: i128) {
+        env.events().publish(
+            (symbol_short!("active"), Self::external_id(env)),
+            (client, deposit),
+        );
+    }
+
+    pub fn draft_withdrawn(env: &Env, client: &Address) {
+        env.events().publish(
+            (symbol_short!("withdraw"), Self::external_id(env)),
+            client,
+        );
+    }
+
     pub fn milestone_completed(
         env: &Env,
         milestone_index: u32,
@@ -32,11 +80,43 @@ impl Events {
         completed_at: u64,
     ) {
         env.events().publish(
-            (symbol_short!("complete"), milestone_index),
+            (symbol_short!("complete"), Self::external_id(env), milestone_index),
             (pr_url.clone(), completed_at),
         );
     }
 
+    pub fn completion_retracted(
+        env: &Env,
+        milestone_index: u32,
+        cosigner: &Address,
+    ) {
+        env.events().publish(
+            (symbol_short!("retract"), Self::external_id(env), milestone_index),
+            cosigner.clone(),
+        );
+    }
+
+    pub fn advance_paid(env: &Env, developer: &Address, amount: i128) {
+        env.events().publish(
+            (symbol_short!("advance"), Self::external_id(env)),
+            (developer, amount),
+        );
+    }
+
+    pub fn late_penalty(env: &Env, milestone_index: u32, days_late: u64, penalty: i128) {
+        env.events().publish(
+            (symbol_short!("late"), Self::external_id(env), milestone_index),
+            (days_late, penalty),
+        );
+    }
+
+    pub fn milestone_reclaimed(env: &Env, milestone_index: u32, client: &Address, amount: i128) {
+        env.events().publish(
+            (symbol_short!("reclaim"), Self::external_id(env), milestone_index),
+            (client, amount),
+        );
+    }
+
     pub fn funds_released(
         env: &Env,
         milestone_index: u32,
@@ -45,11 +125,35 @@ impl Events {
         auto_released: bool,
     ) {
         env.events().publish(
-            (symbol_short!("release"), milestone_index),
+            (symbol_short!("release"), Self::external_id(env), milestone_index),
             (developer, amount, auto_released),
         );
     }
 
+    pub fn keeper_rewarded(
+        env: &Env,
+        milestone_index: u32,
+        keeper: &Address,
+        amount: i128,
+    ) {
+        env.events().publish(
+            (symbol_short!("keeper"), Self::external_id(env), milestone_index),
+            (keeper, amount),
+        );
+    }
+
+    pub fn changes_requested(
+        env: &Env,
+        milestone_index: u32,
+        note_hash: &BytesN<32>,
+        round: u32,
+    ) {
+        env.events().publish(
+            (symbol_short!("changes"), Self::external_id(env), milestone_index),
+            (note_hash.clone(), round),
+        );
+    }
+
     pub fn dispute_opened(
         env: &Env,
         milestone_index: u32,
@@ -57,11 +161,23 @@ impl Events {
         opened_at: u64,
     ) {
         env.events().publish(
-            (symbol_short!("dispute"), milestone_index),
+            (symbol_short!("dispute"), Self::external_id(env), milestone_index),
             (reason.clone(), opened_at),
         );
     }
 
+    pub fn escalated(
+        env: &Env,
+        milestone_index: u32,
+        reason: &String,
+        escalated_at: u64,
+    ) {
+        env.events().publish(
+            (symbol_short!("escalate"), Self::external_id(env), milestone_index),
+            (reason.clone(), escalated_at),
+        );
+    }
+
     pub fn dispute_resolved(
         env: &Env,
         milestone_index: u32,
@@ -69,21 +185,203 @@ impl Events {
         amount: i128,
     ) {
         env.events().publish(
-            (symbol_short!("resolved"), milestone_index),
+            (symbol_short!("resolved"), Self::external_id(env), milestone_index),
+            (recipient, amount),
+        );
+    }
+
+    pub fn change_order_proposed(env: &Env, proposer: &Party, base_version: u32) {
+        env.events().publish(
+            (symbol_short!("co_prop"), Self::external_id(env), base_version),
+            proposer.clone(),
+        );
+    }
+
+    pub fn change_order_withdrawn(env: &Env, party: &Party) {
+        env.events().publish(
+            (symbol_short!("co_drop"), Self::external_id(env)),
+            party.clone(),
+        );
+    }
+
+    /// Versioned snapshot of the milestone set after an accepted change order.
+    pub fn terms_updated(env: &Env, version: u32, milestones: &Vec<Milestone>) {
+        env.events().publish(
+            (symbol_short!("terms"), Self::external_id(env), version),
+            milestones.clone(),
+        );
+    }
+
+    pub fn total_renegotiated(env: &Env, version: u32, old_total: i128, new_total: i128) {
+        env.events().publish(
+            (symbol_short!("total"), Self::external_id(env), version),
+            (old_total, new_total),
+        );
+    }
+
+    pub fn developer_withdrew(env: &Env, developer: &Address, refunded: i128) {
+        env.events().publish(
+            (symbol_short!("dev_quit"), Self::external_id(env)),
+            (developer, refunded),
+        );
+    }
+
+    pub fn signer_added(env: &Env, signer: &Address, expires_at: u64) {
+        env.events().publish(
+            (symbol_short!("sgn_add"), Self::external_id(env)),
+            (signer, expires_at),
+        );
+    }
+
+    pub fn fee_recipient_changed(env: &Env, recipient: &Address) {
+        env.events().publish(
+            (symbol_short!("fee_to"), Self::external_id(env)),
+            recipient,
+        );
+    }
+
+    pub fn admin_removal_requested(env: &Env, removable_at: u64) {
+        env.events().publish(
+            (symbol_short!("admin_rq"), Self::external_id(env)),
+            removable_at,
+        );
+    }
+
+    pub fn signer_admin_changed(env: &Env, admin: &Option<Address>) {
+        env.events().publish(
+            (symbol_short!("sgn_admin"), Self::external_id(env)),
+            admin.clone(),
+        );
+    }
+
+    pub fn signer_removed(env: &Env, signer: &Address) {
+        env.events().publish(
+            (symbol_short!("sgn_rm"), Self::external_id(env)),
+            signer,
+        );
+    }
+
+    pub fn client_transfer_proposed(env: &Env, new_client: &Address) {
+        env.events().publish(
+            (symbol_short!("cl_prop"), Self::external_id(env)),
+            new_client,
+        );
+    }
+
+    pub fn client_transferred(env: &Env, old_client: &Address, new_client: &Address) {
+        env.events().publish(
+            (symbol_short!("cl_moved"), Self::external_id(env)),
+            (old_client, new_client),
+        );
+    }
+
+    pub fn payout_changed(
+        env: &Env,
+        milestone_index: Option<u32>,
+        address: &Address,
+        effective_at: u64,
+    ) {
+        env.events().publish(
+            (symbol_short!("payout"), Self::external_id(env)),
+            (milestone_index, address, effective_at),
+        );
+    }
+
+    pub fn replacement_proposed(env: &Env, new_developer: &Address) {
+        env.events().publish(
+            (symbol_short!("repl_prop"), Self::external_id(env)),
+            new_developer,
+        );
+    }
+
+    pub fn developer_replaced(env: &Env, old_developer: &Address, new_developer: &Address) {
+        env.events().publish(
+            (symbol_short!("replaced"), Self::external_id(env)),
+            (old_developer, new_developer),
+        );
+    }
+
+    pub fn termination_proposed(env: &Env, proposer: &Party, developer_bps: u32) {
+        env.events().publish(
+            (symbol_short!("term_prop"), Self::external_id(env)),
+            (proposer.clone(), developer_bps),
+        );
+    }
+
+    pub fn termination_withdrawn(env: &Env, party: &Party) {
+        env.events().publish(
+            (symbol_short!("term_drop"), Self::external_id(env)),
+            party.clone(),
+        );
+    }
+
+    pub fn terminated(env: &Env, developer_amount: i128, client_amount: i128) {
+        env.events().publish(
+            (symbol_short!("terminate"), Self::external_id(env)),
+            (developer_amount, client_amount),
+        );
+    }
+
+    pub fn cancel_requested(env: &Env, client: &Address, cancel_at: u64) {
+        env.events().publish(
+            (symbol_short!("cancel_rq"), Self::external_id(env)),
+            (client, cancel_at),
+        );
+    }
+
+    pub fn cancelled(env: &Env, client: &Address, refunded: i128, kill_fee: i128) {
+        env.events().publish(
+            (symbol_short!("cancel"), Self::external_id(env)),
+            (client, refunded, kill_fee),
+        );
+    }
+
+    pub fn retention_held(env: &Env, milestone_index: u32, amount: i128) {
+        env.events().publish(
+            (symbol_short!("retain"), Self::external_id(env), milestone_index),
+            amount,
+        );
+    }
+
+    pub fn warranty_started(env: &Env, retained: i128, ends_at: u64) {
+        env.events().publish(
+            (symbol_short!("warranty"), Self::external_id(env)),
+            (retained, ends_at),
+        );
+    }
+
+    pub fn warranty_claimed(env: &Env, reason: &String, timestamp: u64) {
+        env.events().publish(
+            (symbol_short!("w_claim"), Self::external_id(env)),
+            (reason.clone(), timestamp),
+        );
+    }
+
+    pub fn retention_paid(env: &Env, recipient: &Address, amount: i128) {
+        env.events().publish(
+            (symbol_short!("retained"), Self::external_id(env)),
             (recipient, amount),
         );
     }
 
-    pub fn cancelled(env: &Env, client: &Address, refunded: i128) {
+    pub fn expired(env: &Env, refunded: i128, released: i128) {
         env.events().publish(
-            (symbol_short!("cancel"),),
-            (client, refunded),
+            (symbol_short!("expired"), Self::external_id(env)),
+            (refunded, released),
         );
     }
 
     pub fn completed(env: &Env) {
         env.events().publish(
-            (symbol_short!("done"),),
+            (symbol_short!("done"), Self::external_id(env)),
+            (),
+        );
+    }
+
+    /// Terminal like `done`, but the developer walked away mid-project.
+    pub fn withdrawn(env: &Env) {
+        env.events().publish(
+            (symbol_short!("withdrawn"), Self::external_id(env)),
             (),
         );
     }
@@ -5,15 +5,23 @@
 //   storage.rs  → read/write helpers for contract storage
 //   errors.rs   → all error codes
 //   events.rs   → all emitted events
+//   payouts.rs  → coalesced token transfers (one per recipient per call)
+//   registry.rs → optional deadline push to the shared KeeperRegistry
 //   lib.rs      → contract functions (public API)
 //
 // One contract instance = one escrow agreement.
 // Milestones define proportional fund releases tied to GitHub PRs.
 
 #![no_std]
+// initialize() takes the whole deal in one call; the generated client mirrors it.
+#![allow(clippy::too_many_arguments)]
+
+extern crate alloc;
 
 mod errors;
 mod events;
+mod payouts;
+mod registry;
 mod storage;
 mod types;
 
@@ -21,13 +29,45 @@ mod types;
 mod tests;
 
 use soroban_sdk::{
-    contract, contractimpl, token, Address, Env, String, Vec,
+    contract, contractimpl, token, xdr::ToXdr, Address, BytesN, Env, Map, String, Vec,
 };
 
 use errors::Error;
 use events::Events;
+use payouts::Payouts;
+use registry::Registry;
 use storage::Storage;
-use types::{EscrowStatus, Milestone, MilestoneStatus};
+use types::{
+    ApprovalTimeout, BatchResult, ChangeOrder, EscrowConfig, EscrowMetadata, EscrowStatus, FundingCoverage,
+    FundingMode, LatePolicy, Milestone, MilestoneStatus, Party, PayoutChange, PendingChangeOrder,
+    PendingReplacement, PendingTermination, Terms, TermsProposal, WarrantyClaim,
+};
+
+// ─── Limits ───────────────────────────────────────────────────────────────────
+
+/// Max milestones per escrow, including ones dropped by a change order.
+const MAX_MILESTONES: u32 = 10;
+
+/// Platform fee taken from every release, in bps of the release amount (1%).
+const PLATFORM_FEE_BPS: u32 = 100;
+
+/// Max keeper reward, in bps of the release amount. Carved out of the
+/// platform fee, so the platform always keeps at least half of it.
+const MAX_KEEPER_BPS: u32 = 50;
+
+/// Rework rounds a client may request per milestone before they have to
+/// approve or dispute.
+const MAX_CHANGE_REQUESTS: u32 = 3;
+
+/// Max length of EscrowMetadata::external_id — it's an event topic.
+const MAX_EXTERNAL_ID_LEN: u32 = 64;
+
+/// Max retention held back from each release, in bps of the release amount.
+const MAX_RETENTION_BPS: u32 = 2_000;
+
+/// How long a signer admin must stay silent after request_admin_removal()
+/// before the client and developer can remove it (30 days).
+const ADMIN_ABSENCE_SECS: u64 = 30 * 86_400;
 
 // ─── Contract ─────────────────────────────────────────────────────────────────
 
@@ -39,22 +79,35 @@ impl StreamEscrow {
 
     // ─── Initialize ───────────────────────────────────────────────────────────
 
-    /// Initialize the escrow contract.
+    /// Propose an escrow. Called once by the client when deploying.
     ///
-    /// Called once by the client when deploying.
+    /// Nothing is locked yet: the escrow starts as a Draft. The developer
+    /// reviews the terms and calls accept_terms() with their hash (or
+    /// counter_propose()s), then the client calls fund() to deposit and
+    /// activate. See get_terms() / get_terms_hash().
     ///
     /// # Arguments
     /// * `client`         - Address that deposits funds and approves releases
     /// * `developer`      - Address that receives funds on milestone completion
-    /// * `backend`        - Trusted server address allowed to call mark_complete()
+    /// * `backend`        - Platform address: receives the fee (see set_fee_recipient())
+    ///                      and is the first backend signer
     /// * `token`          - USDC token contract address
     /// * `total_amount`   - Total USDC locked (in stroops — 7 decimal places)
-    /// * `milestones`     - Vec of (title, trigger_keyword, bps) — bps must sum to 10000
+    /// * `milestones`     - Vec of (title, trigger_keyword, bps or amount) — see below
     /// * `dispute_window` - Seconds client has to dispute after mark_complete (0 = manual approval always)
+    /// * `config`         - Optional extras (keeper reward, funding mode, ...) — see EscrowConfig
+    /// * `metadata`       - External id, statement-of-work hash, title, repo — see EscrowMetadata
     ///
     /// # Milestone BPS
     /// BPS = basis points. 10000 = 100%. Each milestone gets a % of total_amount.
     /// Example: [3000, 3000, 4000] = 30% + 30% + 40%
+    ///
+    /// # Fixed amounts
+    /// A milestone may instead set `amount` (and bps = 0) for an exact payout.
+    /// Fixed amounts come off the top; bps milestones split the rest and
+    /// their bps must still sum to 10000. Every milestone's exact amount is
+    /// stored here, so amounts always add up to total_amount precisely.
+    /// Example: $5000 total, Design = $1250 fixed, [6000, 4000] bps → $2250 + $1500
     pub fn initialize(
         env: Env,
         client: Address,
@@ -64,78 +117,284 @@ impl StreamEscrow {
         total_amount: i128,
         milestones: Vec<Milestone>,
         dispute_window_secs: u64,
+        config: EscrowConfig,
+        metadata: EscrowMetadata,
     ) -> Result<(), Error> {
         // Prevent re-initialization
         if Storage::is_initialized(&env) {
             return Err(Error::AlreadyInitialized);
         }
 
-        // Validate inputs
-        if total_amount <= 0 {
-            return Err(Error::InvalidAmount);
-        }
-        if milestones.is_empty() {
-            return Err(Error::NoMilestones);
-        }
-        if milestones.len() > 10 {
-            return Err(Error::TooManyMilestones);
-        }
-
-        // Validate milestone BPS sums to exactly 10000
-        let total_bps: u32 = milestones.iter().map(|m| m.bps).sum();
-        if total_bps != 10_000 {
-            return Err(Error::InvalidMilestoneBps);
+        let milestones = Self::prepare_terms(&env, total_amount, milestones, &config)?;
+        if metadata.external_id.is_empty() || metadata.external_id.len() > MAX_EXTERNAL_ID_LEN {
+            return Err(Error::InvalidMetadata);
         }
 
         // Require client authorization
         client.require_auth();
 
-        // Pull USDC from client into this contract
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&client, &env.current_contract_address(), &total_amount);
-
-        // Store everything
+        // Store everything — funds move in fund()
         Storage::set_client(&env, &client);
         Storage::set_developer(&env, &developer);
         Storage::set_backend(&env, &backend);
+        Storage::set_backend_signers(&env, &Map::from_array(&env, [(backend.clone(), 0u64)]));
         Storage::set_token(&env, &token);
         Storage::set_total_amount(&env, total_amount);
         Storage::set_released_amount(&env, 0i128);
-        Storage::set_status(&env, EscrowStatus::Active);
+        Storage::set_advance_amount(&env, 0i128);
+        Storage::set_retained_amount(&env, 0i128);
+        Storage::set_status(&env, EscrowStatus::Draft);
         Storage::set_dispute_window(&env, dispute_window_secs);
+        Storage::set_config(&env, &config);
+        Storage::set_metadata(&env, &metadata);
         Storage::set_milestones(&env, &milestones);
+        Storage::set_terms_version(&env, 0);
         Storage::set_initialized(&env);
 
+        Self::open_proposal(&env, Party::Client)?;
+
         Events::initialized(
             &env,
             &client,
             &developer,
             total_amount,
-            milestones.len() as u32,
+            milestones.len(),
         );
 
         Ok(())
     }
 
+    // ─── Draft ────────────────────────────────────────────────────────────────
+
+    /// Developer agrees to the client's proposed terms.
+    ///
+    /// `terms_hash` must equal get_terms_hash() — the developer commits to
+    /// exactly the terms they reviewed. The client can then fund().
+    /// Harmless on the developer's own counter-proposal.
+    pub fn accept_terms(env: Env, terms_hash: BytesN<32>) -> Result<(), Error> {
+        let developer = Storage::get_developer(&env)?;
+        developer.require_auth();
+
+        let proposal = Self::open_proposal_matching(&env, &terms_hash)?;
+        Storage::set_proposal(&env, &TermsProposal { accepted: true, ..proposal });
+        Events::terms_accepted(&env, &developer, &terms_hash);
+
+        Ok(())
+    }
+
+    /// Developer replaces the proposed amount, milestones, dispute window and
+    /// config with their own. Parties and token stay fixed.
+    ///
+    /// The client accepts a counter-proposal by funding it with fund().
+    pub fn counter_propose(
+        env: Env,
+        total_amount: i128,
+        milestones: Vec<Milestone>,
+        dispute_window_secs: u64,
+        config: EscrowConfig,
+    ) -> Result<(), Error> {
+        let developer = Storage::get_developer(&env)?;
+        developer.require_auth();
+
+        Self::assert_draft(&env)?;
+
+        let milestones = Self::prepare_terms(&env, total_amount, milestones, &config)?;
+
+        Storage::set_total_amount(&env, total_amount);
+        Storage::set_dispute_window(&env, dispute_window_secs);
+        Storage::set_config(&env, &config);
+        Storage::set_milestones(&env, &milestones);
+
+        Self::open_proposal(&env, Party::Developer)?;
+
+        Ok(())
+    }
+
+    /// Client locks the funds and activates the escrow.
+    ///
+    /// Needs the current terms hash and either the developer's
+    /// accept_terms() on a client proposal, or a developer counter-proposal
+    /// (funding it is the client's acceptance).
+    ///
+    /// Upfront escrows deposit total_amount; just-in-time ones deposit the
+    /// advance and the first `funded_ahead` milestones. Any advance is paid
+    /// to the developer right away.
+    pub fn fund(env: Env, terms_hash: BytesN<32>) -> Result<(), Error> {
+        let client = Storage::get_client(&env)?;
+        client.require_auth();
+
+        let proposal = Self::open_proposal_matching(&env, &terms_hash)?;
+        if proposal.proposer == Party::Client && !proposal.accepted {
+            return Err(Error::TermsNotAccepted);
+        }
+
+        let config       = Storage::get_config(&env)?;
+        let total_amount = Storage::get_total_amount(&env)?;
+        let advance      = Self::advance_of(&config, total_amount)?;
+
+        // Upfront: everything is funded now. Just-in-time: nothing yet
+        let upfront = config.funding_mode == FundingMode::Upfront;
+        let mut milestones = Storage::get_milestones(&env)?;
+        for index in 0..milestones.len() {
+            let m = milestones.get(index).ok_or(Error::MilestoneNotFound)?;
+            milestones.set(index, Milestone { funded: upfront, ..m });
+        }
+
+        // Pull USDC from client into this contract (just the advance for
+        // just-in-time escrows — milestones are funded separately)
+        let deposit = if upfront { total_amount } else { advance };
+        if deposit > 0 {
+            let token        = Storage::get_token(&env)?;
+            let token_client = token::Client::new(&env, &token);
+            token_client.transfer(&client, &env.current_contract_address(), &deposit);
+        }
+
+        Storage::set_released_amount(&env, advance);
+        Storage::set_advance_amount(&env, advance);
+        Storage::set_milestones(&env, &milestones);
+        Storage::set_status(&env, EscrowStatus::Active);
+        Storage::set_fee_admin(&env, &config.signer_admin);
+        Storage::remove_proposal(&env);
+
+        // Pay the advance straight out, platform fee applied as on a release
+        if advance > 0 {
+            let developer   = Self::payout_address(&env, None)?;
+            let fee_amount  = Self::bps_of(advance, PLATFORM_FEE_BPS);
+            let mut payouts = Payouts::new(&env);
+            payouts.add(&developer, advance - fee_amount)?;
+            payouts.add(&Storage::get_backend(&env)?, fee_amount)?;
+            payouts.flush(&env)?;
+
+            Events::advance_paid(&env, &developer, advance - fee_amount);
+        }
+
+        // Just-in-time: fund the first `funded_ahead` milestones
+        Self::top_up_funding(&env)?;
+
+        Events::activated(&env, &client, deposit);
+
+        Ok(())
+    }
+
+    /// Client abandons a Draft. Nothing was deposited, so nothing moves;
+    /// the escrow ends Cancelled.
+    pub fn withdraw_draft(env: Env) -> Result<(), Error> {
+        let client = Storage::get_client(&env)?;
+        client.require_auth();
+
+        Self::assert_draft(&env)?;
+
+        Storage::remove_proposal(&env);
+        Storage::set_status(&env, EscrowStatus::Cancelled);
+        Events::draft_withdrawn(&env, &client);
+
+        Ok(())
+    }
+
     // ─── Mark complete ────────────────────────────────────────────────────────
 
     /// Mark a milestone as complete after verifying a GitHub PR merge.
     ///
-    /// Only callable by the backend address (set at initialization).
+    /// Only callable by a backend signer (see add_backend_signer()).
     /// This starts the dispute window timer if dispute_window > 0.
     /// If dispute_window == 0, client must call approve() manually.
     ///
     /// # Arguments
+    /// * `signer`          - Backend signer authorizing the call
     /// * `milestone_index` - 0-based index of the milestone
     /// * `pr_url`          - GitHub PR URL (stored on-chain for transparency)
     pub fn mark_complete(
         env: Env,
+        signer: Address,
         milestone_index: u32,
         pr_url: String,
     ) -> Result<(), Error> {
-        // Only the backend can call this
-        let backend = Storage::get_backend(&env)?;
-        backend.require_auth();
+        // Only a backend signer can call this
+        Self::require_backend(&env, &signer)?;
+
+        Self::assert_active(&env)?;
+        Self::complete_milestone(&env, milestone_index, &pr_url)?;
+
+        Ok(())
+    }
+
+    // ─── Complete by trigger ──────────────────────────────────────────────────
+
+    /// Mark the milestone whose trigger_keyword matches a PR as complete.
+    ///
+    /// Same effect as mark_complete(), but the milestone is picked on-chain:
+    /// a Pending milestone matches when its trigger_keyword appears in the
+    /// PR title or in any label (ASCII case-insensitive substring match).
+    /// Exactly one milestone must match — ambiguous PRs are rejected so the
+    /// keyword stored here, not the backend's DB, decides what gets paid.
+    ///
+    /// Only callable by a backend signer.
+    ///
+    /// # Arguments
+    /// * `signer`   - Backend signer authorizing the call
+    /// * `title`    - PR title
+    /// * `labels`   - PR labels
+    /// * `evidence` - GitHub PR URL (stored as the milestone's pr_url)
+    ///
+    /// Returns the index of the milestone that was completed.
+    pub fn complete_by_trigger(
+        env: Env,
+        signer: Address,
+        title: String,
+        labels: Vec<String>,
+        evidence: String,
+    ) -> Result<u32, Error> {
+        Self::require_backend(&env, &signer)?;
+
+        Self::assert_active(&env)?;
+
+        let milestones = Storage::get_milestones(&env)?;
+        let mut matched: Option<u32> = None;
+
+        for (index, milestone) in milestones.iter().enumerate() {
+            if milestone.status != MilestoneStatus::Pending {
+                continue;
+            }
+
+            let hit = contains_ignore_case(&title, &milestone.trigger_keyword)
+                || labels
+                    .iter()
+                    .any(|label| contains_ignore_case(&label, &milestone.trigger_keyword));
+
+            if hit {
+                if matched.is_some() {
+                    return Err(Error::AmbiguousTrigger);
+                }
+                matched = Some(index as u32);
+            }
+        }
+
+        let milestone_index = matched.ok_or(Error::NoTriggerMatch)?;
+        Self::complete_milestone(&env, milestone_index, &evidence)?;
+
+        Ok(milestone_index)
+    }
+
+    // ─── Retract completion ───────────────────────────────────────────────────
+
+    /// Undo a mark_complete() that matched the wrong PR.
+    ///
+    /// Returns the milestone to Pending and clears pr_url / completed_at.
+    /// Needs a backend signer plus a co-signer who is either the client or the
+    /// developer — the backend alone can't use this to sit on earned funds.
+    /// Work still owed to a replaced developer can't be retracted.
+    pub fn retract_completion(
+        env: Env,
+        signer: Address,
+        milestone_index: u32,
+        cosigner: Address,
+    ) -> Result<(), Error> {
+        Self::require_backend(&env, &signer)?;
+
+        if cosigner != Storage::get_client(&env)? && cosigner != Storage::get_developer(&env)? {
+            return Err(Error::NotAParty);
+        }
+        cosigner.require_auth();
 
         Self::assert_active(&env)?;
 
@@ -144,23 +403,24 @@ impl StreamEscrow {
             .get(milestone_index)
             .ok_or(Error::MilestoneNotFound)?;
 
-        if milestone.status != MilestoneStatus::Pending {
+        if milestone.status != MilestoneStatus::PendingRelease {
+            return Err(Error::MilestoneNotPendingRelease);
+        }
+        // Owed to a replaced developer, who isn't here to co-sign
+        if milestone.payee.is_some() {
             return Err(Error::MilestoneAlreadyCompleted);
         }
 
-        // Update milestone state
-        let completed_at = env.ledger().timestamp();
-        let updated = Milestone {
-            status:       MilestoneStatus::PendingRelease,
-            pr_url:       Some(pr_url.clone()),
-            completed_at: Some(completed_at),
+        milestones.set(milestone_index, Milestone {
+            status:       MilestoneStatus::Pending,
+            pr_url:       None,
+            completed_at: None,
             ..milestone
-        };
-
-        milestones.set(milestone_index, updated);
+        });
         Storage::set_milestones(&env, &milestones);
+        Registry::untrack(&env, milestone_index)?;
 
-        Events::milestone_completed(&env, milestone_index, &pr_url, completed_at);
+        Events::completion_retracted(&env, milestone_index, &cosigner);
 
         Ok(())
     }
@@ -171,12 +431,16 @@ impl StreamEscrow {
     ///
     /// Can be called any time after mark_complete(), even during dispute window.
     /// This is the fast path — no waiting needed if client is happy.
+    ///
+    /// In just-in-time funding mode this also tops the escrow back up to
+    /// `funded_ahead` funded milestones, pulling the difference from the client.
     pub fn approve(env: Env, milestone_index: u32) -> Result<(), Error> {
         let client = Storage::get_client(&env)?;
         client.require_auth();
 
         Self::assert_active(&env)?;
-        Self::release_milestone(&env, milestone_index, false)?;
+        Self::release_milestone(&env, milestone_index, &None, false)?;
+        Self::top_up_funding(&env)?;
 
         Ok(())
     }
@@ -187,34 +451,172 @@ impl StreamEscrow {
     ///
     /// Callable by anyone — no authorization required.
     /// This makes the contract truly trustless after the window expires.
-    pub fn auto_release(env: Env, milestone_index: u32) -> Result<(), Error> {
+    ///
+    /// If `keeper` is given (and signs), it receives the keeper reward
+    /// configured at init, carved out of the platform fee.
+    pub fn auto_release(
+        env: Env,
+        milestone_index: u32,
+        keeper: Option<Address>,
+    ) -> Result<(), Error> {
         Self::assert_active(&env)?;
+        Self::require_keeper_auth(&keeper);
 
         let milestones = Storage::get_milestones(&env)?;
         let milestone = milestones
             .get(milestone_index)
             .ok_or(Error::MilestoneNotFound)?;
 
+        Self::assert_auto_releasable(&env, &milestone)?;
+        Self::release_milestone(&env, milestone_index, &keeper, true)?;
+
+        Ok(())
+    }
+
+    // ─── Batch operations ─────────────────────────────────────────────────────
+
+    /// Backend marks several milestones complete in one transaction.
+    ///
+    /// Each entry is (milestone_index, pr_url). With `atomic == false` a bad
+    /// entry is reported in its BatchResult and the rest still go through;
+    /// with `atomic == true` the first failure aborts the whole call.
+    pub fn mark_complete_many(
+        env: Env,
+        signer: Address,
+        completions: Vec<(u32, String)>,
+        atomic: bool,
+    ) -> Result<Vec<BatchResult>, Error> {
+        Self::require_backend(&env, &signer)?;
+
+        Self::assert_active(&env)?;
+
+        let mut results = Vec::new(&env);
+        for (milestone_index, pr_url) in completions.iter() {
+            let outcome = Self::complete_milestone(&env, milestone_index, &pr_url);
+            Self::push_result(&mut results, milestone_index, outcome, atomic)?;
+        }
+
+        Ok(results)
+    }
+
+    /// Client approves several milestones in one transaction.
+    ///
+    /// Releases are coalesced into one transfer to the developer and one fee
+    /// transfer. `atomic` behaves as in mark_complete_many().
+    pub fn approve_many(
+        env: Env,
+        milestone_indices: Vec<u32>,
+        atomic: bool,
+    ) -> Result<Vec<BatchResult>, Error> {
+        let client = Storage::get_client(&env)?;
+        client.require_auth();
+
+        Self::assert_active(&env)?;
+
+        let mut payouts = Payouts::new(&env);
+        let mut results = Vec::new(&env);
+        for milestone_index in milestone_indices.iter() {
+            let outcome = Self::queue_release(&env, milestone_index, &None, false, &mut payouts);
+            Self::push_result(&mut results, milestone_index, outcome, atomic)?;
+        }
+
+        payouts.flush(&env)?;
+        Self::check_completion(&env)?;
+        Self::top_up_funding(&env)?;
+
+        Ok(results)
+    }
+
+    /// Release every milestone whose dispute window has expired.
+    ///
+    /// Callable by anyone. Returns one BatchResult per PendingRelease
+    /// milestone — those still inside their window report the reason.
+    /// `keeper` is rewarded per released milestone, as in auto_release().
+    pub fn auto_release_all(
+        env: Env,
+        keeper: Option<Address>,
+    ) -> Result<Vec<BatchResult>, Error> {
+        Self::assert_active(&env)?;
+        Self::require_keeper_auth(&keeper);
+
+        let milestones  = Storage::get_milestones(&env)?;
+        let mut payouts = Payouts::new(&env);
+        let mut results = Vec::new(&env);
+
+        for (index, milestone) in milestones.iter().enumerate() {
+            if milestone.status != MilestoneStatus::PendingRelease {
+                continue;
+            }
+
+            let milestone_index = index as u32;
+            let outcome = Self::assert_auto_releasable(&env, &milestone)
+                .and_then(|_| {
+                    Self::queue_release(&env, milestone_index, &keeper, true, &mut payouts)
+                });
+            Self::push_result(&mut results, milestone_index, outcome, false)?;
+        }
+
+        payouts.flush(&env)?;
+        Self::check_completion(&env)?;
+
+        Ok(results)
+    }
+
+    // ─── Request changes ──────────────────────────────────────────────────────
+
+    /// Client sends a completed milestone back for rework.
+    ///
+    /// Lighter than dispute(): the milestone returns to Pending with its
+    /// completion cleared, so the next mark_complete() starts a fresh review
+    /// window. Only allowed while the review is still open (before the dispute
+    /// window or approval deadline ends) and at most MAX_CHANGE_REQUESTS
+    /// times per milestone.
+    ///
+    /// # Arguments
+    /// * `note_hash` - Hash of the off-chain feedback (e.g. sha256 of the review comment)
+    pub fn request_changes(
+        env: Env,
+        milestone_index: u32,
+        note_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        let client = Storage::get_client(&env)?;
+        client.require_auth();
+
+        Self::assert_active(&env)?;
+
+        let mut milestones = Storage::get_milestones(&env)?;
+        let milestone = milestones
+            .get(milestone_index)
+            .ok_or(Error::MilestoneNotFound)?;
+
         if milestone.status != MilestoneStatus::PendingRelease {
             return Err(Error::MilestoneNotPendingRelease);
         }
-
-        let dispute_window = Storage::get_dispute_window(&env)?;
-        if dispute_window == 0 {
-            return Err(Error::ManualApprovalRequired);
+        if milestone.change_requests >= MAX_CHANGE_REQUESTS {
+            return Err(Error::ChangeRequestLimitReached);
         }
 
         let completed_at = milestone.completed_at.ok_or(Error::MilestoneNotCompleted)?;
-        let now          = env.ledger().timestamp();
-        let deadline     = completed_at
-            .checked_add(dispute_window)
-            .ok_or(Error::Overflow)?;
-
-        if now < deadline {
-            return Err(Error::DisputeWindowOpen);
+        if let Some(deadline) = Self::review_deadline(&env, completed_at)? {
+            if env.ledger().timestamp() >= deadline {
+                return Err(Error::ReviewWindowClosed);
+            }
         }
 
-        Self::release_milestone(&env, milestone_index, true)?;
+        let round = milestone.change_requests + 1;
+        milestones.set(milestone_index, Milestone {
+            status:          MilestoneStatus::Pending,
+            pr_url:          None,
+            completed_at:    None,
+            change_requests: round,
+            // Rework is done by the current developer, so they get paid
+            payee:           None,
+            ..milestone
+        });
+        Storage::set_milestones(&env, &milestones);
+        Registry::untrack(&env, milestone_index)?;
+
+        Events::changes_requested(&env, milestone_index, &note_hash, round);
 
         Ok(())
     }
@@ -261,25 +663,78 @@ impl StreamEscrow {
         };
         milestones.set(milestone_index, disputed);
         Storage::set_milestones(&env, &milestones);
+        Registry::untrack(&env, milestone_index)?;
 
         Events::dispute_opened(&env, milestone_index, &reason, now);
 
         Ok(())
     }
 
+    // ─── Escalate ─────────────────────────────────────────────────────────────
+
+    /// Developer escalates a milestone the client never approved.
+    ///
+    /// Manual-approval escrows only (dispute_window == 0), configured with
+    /// ApprovalTimeout::Escalate. Once approval_deadline_secs have passed
+    /// since mark_complete(), the milestone moves to Disputed and is settled
+    /// by resolve_dispute() like any other dispute.
+    pub fn escalate(env: Env, milestone_index: u32, reason: String) -> Result<(), Error> {
+        let developer = Storage::get_developer(&env)?;
+        developer.require_auth();
+
+        Self::assert_active(&env)?;
+
+        let config = Storage::get_config(&env)?;
+        if Storage::get_dispute_window(&env)? > 0
+            || config.approval_deadline_secs == 0
+            || config.approval_timeout != ApprovalTimeout::Escalate
+        {
+            return Err(Error::EscalationNotAvailable);
+        }
+
+        let mut milestones = Storage::get_milestones(&env)?;
+        let milestone = milestones
+            .get(milestone_index)
+            .ok_or(Error::MilestoneNotFound)?;
+
+        if milestone.status != MilestoneStatus::PendingRelease {
+            return Err(Error::MilestoneNotPendingRelease);
+        }
+
+        let completed_at = milestone.completed_at.ok_or(Error::MilestoneNotCompleted)?;
+        let now          = env.ledger().timestamp();
+        let deadline     = completed_at
+            .checked_add(config.approval_deadline_secs)
+            .ok_or(Error::Overflow)?;
+
+        if now < deadline {
+            return Err(Error::ApprovalDeadlineOpen);
+        }
+
+        milestones.set(milestone_index, Milestone {
+            status: MilestoneStatus::Disputed,
+            ..milestone
+        });
+        Storage::set_milestones(&env, &milestones);
+
+        Events::escalated(&env, milestone_index, &reason, now);
+
+        Ok(())
+    }
+
     // ─── Resolve dispute ──────────────────────────────────────────────────────
 
     /// Resolve a disputed milestone — send funds to developer or refund client.
     ///
-    /// Only callable by the backend (arbitrator for V1).
+    /// Only callable by a backend signer (arbitrator for V1).
     /// V2: replace with decentralized arbitration oracle.
     pub fn resolve_dispute(
         env: Env,
+        signer: Address,
         milestone_index: u32,
         release_to_developer: bool,
     ) -> Result<(), Error> {
-        let backend = Storage::get_backend(&env)?;
-        backend.require_auth();
+        Self::require_backend(&env, &signer)?;
 
         Self::assert_active(&env)?;
 
@@ -299,11 +754,10 @@ impl StreamEscrow {
                 ..milestone
             });
             Storage::set_milestones(&env, &milestones);
-            Self::release_milestone(&env, milestone_index, false)?;
+            Self::release_milestone(&env, milestone_index, &None, false)?;
         } else {
             // Client wins — refund this milestone's amount
-            let total_amount = Storage::get_total_amount(&env)?;
-            let refund_amount = Self::milestone_amount(total_amount, milestone.bps);
+            let refund_amount = milestone.amount;
 
             let token   = Storage::get_token(&env)?;
             let client  = Storage::get_client(&env)?;
@@ -331,19 +785,1550 @@ impl StreamEscrow {
         Ok(())
     }
 
-    // ─── Cancel ───────────────────────────────────────────────────────────────
+    // ─── Expire ───────────────────────────────────────────────────────────────
 
-    /// Cancel the escrow and refund all unreleased funds to client.
+    /// Settle everything left once config.expires_at has passed, so funds
+    /// never get stuck when a party disappears. Anyone can call this.
     ///
-    /// Only callable by client, and only if no milestones are PendingRelease or Disputed.
-    /// (Can't cancel mid-review — prevents client from cancelling to avoid payment.)
-    pub fn cancel(env: Env) -> Result<(), Error> {
-        let client = Storage::get_client(&env)?;
-        client.require_auth();
+    /// * Pending milestones        → refunded to the client (if funded)
+    /// * PendingRelease milestones → released, once their review window has
+    ///                               closed (fails while one is still open)
+    /// * Disputed milestones       → config.dispute_default wins
+    /// * Retention                 → developer, or dispute_default if a
+    ///                               warranty claim is open
+    ///
+    /// The escrow ends Expired. As with auto_release(), a signing `keeper`
+    /// gets config.keeper_bps of each milestone released here.
+    pub fn expire(env: Env, keeper: Option<Address>) -> Result<(), Error> {
+        let status = Storage::get_status(&env)?;
+        if status != EscrowStatus::Warranty {
+            Self::assert_active(&env)?;
+        }
+        Self::require_keeper_auth(&keeper);
 
-        Self::assert_active(&env)?;
+        let config = Storage::get_config(&env)?;
+        if config.expires_at == 0 {
+            return Err(Error::DeadlineNotReached);
+        }
+        let now = env.ledger().timestamp();
+        if now < config.expires_at {
+            return Err(Error::DeadlineNotReached);
+        }
 
-        let milestones = Storage::get_milestones(&env)?;
+        let client   = Storage::get_client(&env)?;
+        let mut payouts = Payouts::new(&env);
+        let mut refunded: i128 = 0;
+        let mut released: i128 = 0;
+
+        let count = Storage::get_milestones(&env)?.len();
+        for index in 0..count {
+            let mut milestones = Storage::get_milestones(&env)?;
+            let milestone = milestones.get(index).ok_or(Error::MilestoneNotFound)?;
+
+            let release = match milestone.status {
+                MilestoneStatus::Pending => false,
+                MilestoneStatus::PendingRelease => {
+                    let completed_at = milestone.completed_at.ok_or(Error::MilestoneNotCompleted)?;
+                    if let Some(deadline) = Self::review_deadline(&env, completed_at)? {
+                        if now < deadline {
+                            return Err(Error::DisputeWindowOpen);
+                        }
+                    }
+                    true
+                }
+                MilestoneStatus::Disputed => config.dispute_default == Party::Developer,
+                _ => continue,
+            };
+
+            if release {
+                milestones.set(index, Milestone {
+                    status: MilestoneStatus::PendingRelease,
+                    ..milestone
+                });
+                Storage::set_milestones(&env, &milestones);
+                Self::queue_release(&env, index, &keeper, true, &mut payouts)?;
+                released += milestone.amount;
+                continue;
+            }
+
+            let amount = if milestone.funded { milestone.amount } else { 0 };
+            payouts.add(&client, amount)?;
+            refunded += amount;
+
+            milestones.set(index, Milestone {
+                status: MilestoneStatus::Refunded,
+                ..milestone
+            });
+            Storage::set_milestones(&env, &milestones);
+            Registry::untrack(&env, index)?;
+
+            let total_released = Storage::get_released_amount(&env)?;
+            Storage::set_released_amount(&env, total_released + milestone.amount);
+        }
+
+        payouts.flush(&env)?;
+
+        // Retention: an open warranty claim falls to the default outcome
+        let claim_open = Storage::get_warranty_claim(&env).is_some();
+        let retention_to = if claim_open && config.dispute_default == Party::Client {
+            client.clone()
+        } else {
+            Self::payout_address(&env, None)?
+        };
+        Storage::remove_warranty_claim(&env);
+        Self::pay_retention(&env, &retention_to)?;
+
+        Storage::set_status(&env, EscrowStatus::Expired);
+        Events::expired(&env, refunded, released);
+
+        Ok(())
+    }
+
+    // ─── Developer withdraw ───────────────────────────────────────────────────
+
+    /// Developer steps away from the engagement.
+    ///
+    /// Every Pending milestone is refunded to the client right away (funded
+    /// ones only, in just-in-time mode). Milestones already in review or
+    /// dispute still resolve normally. Once nothing is left the escrow ends
+    /// Withdrawn rather than Completed, so reputation systems can tell the two
+    /// apart.
+    pub fn developer_withdraw(env: Env) -> Result<(), Error> {
+        let developer = Storage::get_developer(&env)?;
+        developer.require_auth();
+
+        Self::assert_active(&env)?;
+
+        let client         = Storage::get_client(&env)?;
+        let mut milestones = Storage::get_milestones(&env)?;
+        let mut refunded: i128 = 0;
+        let mut settled: i128  = 0;
+
+        for index in 0..milestones.len() {
+            let m = milestones.get(index).ok_or(Error::MilestoneNotFound)?;
+            if m.status != MilestoneStatus::Pending {
+                continue;
+            }
+            if m.funded {
+                refunded = refunded.checked_add(m.amount).ok_or(Error::Overflow)?;
+            }
+            settled = settled.checked_add(m.amount).ok_or(Error::Overflow)?;
+            milestones.set(index, Milestone { status: MilestoneStatus::Refunded, ..m });
+        }
+
+        let mut payouts = Payouts::new(&env);
+        payouts.add(&client, refunded)?;
+        payouts.flush(&env)?;
+
+        Storage::set_milestones(&env, &milestones);
+        let released = Storage::get_released_amount(&env)?;
+        Storage::set_released_amount(&env, released + settled);
+        Storage::set_developer_withdrawn(&env);
+
+        Events::developer_withdrew(&env, &developer, refunded);
+
+        Self::check_completion(&env)?;
+
+        Ok(())
+    }
+
+    // ─── Reclaim ──────────────────────────────────────────────────────────────
+
+    /// Client takes back a milestone that blew through its hard deadline.
+    ///
+    /// Needs LatePolicy::Reclaim(grace) and a due_at on the milestone. Once
+    /// due_at + grace has passed with the milestone still Pending, its funds
+    /// go back to the client and it ends Refunded — the rest of the escrow
+    /// carries on, unlike cancel().
+    pub fn reclaim_milestone(env: Env, milestone_index: u32) -> Result<(), Error> {
+        let client = Storage::get_client(&env)?;
+        client.require_auth();
+
+        Self::assert_active(&env)?;
+
+        let mut milestones = Storage::get_milestones(&env)?;
+        let milestone = milestones
+            .get(milestone_index)
+            .ok_or(Error::MilestoneNotFound)?;
+
+        if milestone.status != MilestoneStatus::Pending {
+            return Err(Error::MilestoneNotPending);
+        }
+
+        let grace = match Storage::get_config(&env)?.late_policy {
+            LatePolicy::Reclaim(grace) => grace,
+            _ => return Err(Error::ReclaimNotAvailable),
+        };
+        let due_at   = milestone.due_at.ok_or(Error::ReclaimNotAvailable)?;
+        let deadline = due_at.checked_add(grace).ok_or(Error::Overflow)?;
+        if env.ledger().timestamp() < deadline {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        // Just-in-time milestones that were never funded have nothing to send
+        let amount   = milestone.amount;
+        let refunded = if milestone.funded { amount } else { 0 };
+        let mut payouts = Payouts::new(&env);
+        payouts.add(&client, refunded)?;
+        payouts.flush(&env)?;
+
+        milestones.set(milestone_index, Milestone {
+            status: MilestoneStatus::Refunded,
+            ..milestone
+        });
+        Storage::set_milestones(&env, &milestones);
+
+        let released = Storage::get_released_amount(&env)?;
+        Storage::set_released_amount(&env, released + amount);
+
+        Events::milestone_reclaimed(&env, milestone_index, &client, refunded);
+
+        Self::check_completion(&env)?;
+
+        Ok(())
+    }
+
+    // ─── Warranty ─────────────────────────────────────────────────────────────
+
+    /// Client raises a claim against the retention during the warranty period.
+    ///
+    /// Blocks release_retention() until the backend settles it with
+    /// resolve_warranty_claim().
+    pub fn claim_warranty(env: Env, reason: String) -> Result<(), Error> {
+        let client = Storage::get_client(&env)?;
+        client.require_auth();
+
+        if Storage::get_status(&env)? != EscrowStatus::Warranty {
+            return Err(Error::NotInWarranty);
+        }
+        if Storage::get_warranty_claim(&env).is_some() {
+            return Err(Error::WarrantyClaimOpen);
+        }
+
+        let now     = env.ledger().timestamp();
+        let ends_at = Storage::get_warranty_ends_at(&env).ok_or(Error::NotInWarranty)?;
+        if now >= ends_at {
+            return Err(Error::NotInWarranty);
+        }
+
+        Storage::set_warranty_claim(&env, &WarrantyClaim { reason: reason.clone(), raised_at: now });
+        Events::warranty_claimed(&env, &reason, now);
+
+        Ok(())
+    }
+
+    /// Settle a warranty claim — retention goes to the developer or back to
+    /// the client. Either way the escrow is Completed.
+    ///
+    /// Only callable by a backend signer (arbitrator for V1), like resolve_dispute().
+    pub fn resolve_warranty_claim(
+        env: Env,
+        signer: Address,
+        release_to_developer: bool,
+    ) -> Result<(), Error> {
+        Self::require_backend(&env, &signer)?;
+
+        if Storage::get_warranty_claim(&env).is_none() {
+            return Err(Error::NothingPending);
+        }
+
+        let recipient = if release_to_developer {
+            Self::payout_address(&env, None)?
+        } else {
+            Storage::get_client(&env)?
+        };
+
+        Storage::remove_warranty_claim(&env);
+        Self::pay_retention(&env, &recipient)?;
+        Self::finish(&env);
+
+        Ok(())
+    }
+
+    /// Release the retention to the developer once the warranty period has
+    /// passed without an open claim. Anyone can call this.
+    pub fn release_retention(env: Env) -> Result<(), Error> {
+        if Storage::get_status(&env)? != EscrowStatus::Warranty {
+            return Err(Error::NotInWarranty);
+        }
+        if Storage::get_warranty_claim(&env).is_some() {
+            return Err(Error::WarrantyClaimOpen);
+        }
+
+        let ends_at = Storage::get_warranty_ends_at(&env).ok_or(Error::NotInWarranty)?;
+        if env.ledger().timestamp() < ends_at {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        Self::pay_retention(&env, &Self::payout_address(&env, None)?)?;
+        Self::finish(&env);
+
+        Ok(())
+    }
+
+    // ─── Funding ──────────────────────────────────────────────────────────────
+
+    /// Client funds one milestone (just-in-time funding mode).
+    ///
+    /// Pulls exactly the milestone's amount. A milestone must be funded
+    /// before mark_complete() accepts it.
+    pub fn fund_milestone(env: Env, milestone_index: u32) -> Result<(), Error> {
+        let client = Storage::get_client(&env)?;
+        client.require_auth();
+
+        Self::assert_active(&env)?;
+        Self::assert_not_expired(&env)?;
+
+        let mut milestones = Storage::get_milestones(&env)?;
+        let milestone = milestones
+            .get(milestone_index)
+            .ok_or(Error::MilestoneNotFound)?;
+
+        if milestone.status != MilestoneStatus::Pending {
+            return Err(Error::MilestoneNotPending);
+        }
+        if milestone.funded {
+            return Err(Error::MilestoneAlreadyFunded);
+        }
+
+        let token = Storage::get_token(&env)?;
+        token::Client::new(&env, &token).transfer(
+            &client,
+            &env.current_contract_address(),
+            &milestone.amount,
+        );
+
+        let amount = milestone.amount;
+        milestones.set(milestone_index, Milestone { funded: true, ..milestone });
+        Storage::set_milestones(&env, &milestones);
+
+        Events::milestone_funded(&env, milestone_index, amount);
+
+        Ok(())
+    }
+
+    // ─── Renegotiate total ────────────────────────────────────────────────────
+
+    /// Change total_amount by mutual agreement — needs client and developer auth.
+    ///
+    /// Released and refunded amounts stay fixed, and so do fixed-amount
+    /// milestones; every bps milestone that hasn't settled yet keeps its bps
+    /// proportion of the new remainder. An increase pulls the difference from
+    /// the client, a decrease refunds it (for just-in-time escrows, only the
+    /// part that affects already-funded milestones).
+    pub fn renegotiate_total(env: Env, new_total: i128) -> Result<u32, Error> {
+        let client    = Storage::get_client(&env)?;
+        let developer = Storage::get_developer(&env)?;
+        client.require_auth();
+        developer.require_auth();
+
+        Self::assert_active(&env)?;
+
+        // Something must be left to pay for the milestones still open
+        let released = Storage::get_released_amount(&env)?;
+        if new_total <= released {
+            return Err(Error::InvalidAmount);
+        }
+
+        let old_total = Storage::get_total_amount(&env)?;
+
+        // Fixed amounts stay fixed — bps milestones absorb the difference
+        let mut milestones = Storage::get_milestones(&env)?;
+        let held_before    = Self::held_amount(&milestones);
+        Self::allocate_amounts(&mut milestones, new_total, released, false)?;
+
+        // Only funded milestones move money (all of them in upfront mode)
+        let delta = Self::held_amount(&milestones)
+            .checked_sub(held_before)
+            .ok_or(Error::Overflow)?;
+
+        let token        = Storage::get_token(&env)?;
+        let token_client = token::Client::new(&env, &token);
+        if delta > 0 {
+            token_client.transfer(&client, &env.current_contract_address(), &delta);
+        } else if delta < 0 {
+            token_client.transfer(&env.current_contract_address(), &client, &-delta);
+        }
+
+        let version = Storage::get_terms_version(&env)? + 1;
+        Storage::set_total_amount(&env, new_total);
+        Storage::set_milestones(&env, &milestones);
+        Storage::set_terms_version(&env, version);
+
+        Events::total_renegotiated(&env, version, old_total, new_total);
+
+        Ok(version)
+    }
+
+    // ─── Cancel ───────────────────────────────────────────────────────────────
+
+    /// Cancel the escrow and refund all unreleased funds to client, less
+    /// any config.kill_fee_bps kill fee paid to the developer.
+    ///
+    /// Only callable by client, and only if no milestones are PendingRelease or Disputed.
+    /// (Can't cancel mid-review — prevents client from cancelling to avoid payment.)
+    /// See request_cancel() for a wind-down that lets in-progress work finish;
+    /// once notice is given the client can't skip it by cancelling outright.
+    pub fn cancel(env: Env) -> Result<(), Error> {
+        let client = Storage::get_client(&env)?;
+        client.require_auth();
+
+        Self::assert_active(&env)?;
+
+        if Storage::get_cancel_at(&env).is_some() {
+            return Err(Error::CancelAlreadyRequested);
+        }
+        Self::close_out(&env, &client)
+    }
+
+    /// Client gives notice of cancellation instead of cancelling outright.
+    ///
+    /// For config.cancel_notice_secs the escrow runs as normal: the backend
+    /// can still mark_complete() work in progress and reviews carry on. After
+    /// that, no new milestone can be completed and anyone can
+    /// finalize_cancel() once open reviews have settled.
+    pub fn request_cancel(env: Env) -> Result<(), Error> {
+        let client = Storage::get_client(&env)?;
+        client.require_auth();
+
+        Self::assert_active(&env)?;
+
+        if Storage::get_cancel_at(&env).is_some() {
+            return Err(Error::CancelAlreadyRequested);
+        }
+
+        let notice    = Storage::get_config(&env)?.cancel_notice_secs;
+        let cancel_at = env
+            .ledger()
+            .timestamp()
+            .checked_add(notice)
+            .ok_or(Error::Overflow)?;
+        Storage::set_cancel_at(&env, cancel_at);
+
+        Events::cancel_requested(&env, &client, cancel_at);
+
+        Ok(())
+    }
+
+    /// Close a wound-down escrow once its notice period is over: refund the
+    /// remaining Pending milestones and mark it Cancelled. Anyone can call this.
+    ///
+    /// Fails while a milestone is still PendingRelease or Disputed — those
+    /// finish through the normal approve / auto_release / dispute flow first.
+    pub fn finalize_cancel(env: Env) -> Result<(), Error> {
+        Self::assert_active(&env)?;
+
+        let cancel_at = Storage::get_cancel_at(&env).ok_or(Error::NothingPending)?;
+        if env.ledger().timestamp() < cancel_at {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        Self::close_out(&env, &Storage::get_client(&env)?)
+    }
+
+    // ─── Change orders ────────────────────────────────────────────────────────
+
+    /// Propose a change to the milestone set. The other party must accept.
+    ///
+    /// A change order can append new milestones, drop Pending ones (they stay
+    /// in the list as Removed, so indices never shift) and set new bps on
+    /// Pending ones. Released, Refunded and in-flight milestones can't be
+    /// touched, and bps across all milestones must still sum to 10_000.
+    /// Pending bps milestones then split whatever the fixed-amount and
+    /// in-flight milestones leave of the unreleased total.
+    ///
+    /// Replaces any earlier proposal. Validated up front and again on accept.
+    pub fn propose_change_order(
+        env: Env,
+        proposer: Party,
+        order: ChangeOrder,
+    ) -> Result<(), Error> {
+        Self::party_address(&env, &proposer)?.require_auth();
+        Self::assert_active(&env)?;
+
+        // Dry run — reject orders that can't apply to the current milestones
+        Self::apply_change_order(&env, &order)?;
+
+        let base_version = Storage::get_terms_version(&env)?;
+        Storage::set_change_order(&env, &PendingChangeOrder {
+            proposer: proposer.clone(),
+            base_version,
+            order,
+        });
+
+        Events::change_order_proposed(&env, &proposer, base_version);
+
+        Ok(())
+    }
+
+    /// Accept the pending change order. Callable by the party that didn't propose it.
+    ///
+    /// `base_version` must match the terms version the order was proposed
+    /// against, so nobody accepts a proposal written for older terms.
+    pub fn accept_change_order(env: Env, base_version: u32) -> Result<u32, Error> {
+        let pending = Storage::get_change_order(&env).ok_or(Error::NothingPending)?;
+        let acceptor = Self::counterparty(&pending.proposer);
+        Self::party_address(&env, &acceptor)?.require_auth();
+
+        Self::assert_active(&env)?;
+
+        let current = Storage::get_terms_version(&env)?;
+        if pending.base_version != base_version || current != base_version {
+            return Err(Error::StaleChangeOrder);
+        }
+
+        let old_held   = Self::held_amount(&Storage::get_milestones(&env)?);
+        let milestones = Self::apply_change_order(&env, &pending.order)?;
+        let version    = current + 1;
+
+        // Just-in-time: funding for dropped or re-priced milestones goes back
+        let refund = old_held
+            .checked_sub(Self::held_amount(&milestones))
+            .ok_or(Error::Overflow)?;
+        let mut payouts = Payouts::new(&env);
+        payouts.add(&Storage::get_client(&env)?, refund)?;
+        payouts.flush(&env)?;
+
+        Storage::set_milestones(&env, &milestones);
+        Storage::set_terms_version(&env, version);
+        Storage::remove_change_order(&env);
+
+        Events::terms_updated(&env, version, &milestones);
+
+        // Dropping the last open milestones can finish the escrow
+        Self::check_completion(&env)?;
+
+        Ok(version)
+    }
+
+    /// Withdraw (proposer) or reject (counterparty) the pending change order.
+    pub fn withdraw_change_order(env: Env, party: Party) -> Result<(), Error> {
+        Self::party_address(&env, &party)?.require_auth();
+
+        if Storage::get_change_order(&env).is_none() {
+            return Err(Error::NothingPending);
+        }
+        Storage::remove_change_order(&env);
+
+        Events::change_order_withdrawn(&env, &party);
+
+        Ok(())
+    }
+
+    // ─── Backend signers ──────────────────────────────────────────────────────
+
+    /// Authorize `signer` for the backend-only calls (mark_complete(),
+    /// resolve_dispute(), …) until `expires_at` (0 = no expiry). Re-adding a
+    /// signer updates its expiry — rotate a key by adding the new one and
+    /// giving the old one an expiry. At least one signer must stay without
+    /// an expiry, so the set can't run out on its own.
+    ///
+    /// Needs config.signer_admin, or the client and developer together when
+    /// no admin is configured (see request_admin_removal() for a lost admin).
+    pub fn add_backend_signer(env: Env, signer: Address, expires_at: u64) -> Result<(), Error> {
+        Self::require_signer_admin(&env)?;
+
+        if expires_at != 0 && expires_at <= env.ledger().timestamp() {
+            return Err(Error::InvalidConfig);
+        }
+
+        let mut signers = Storage::get_backend_signers(&env)?;
+        signers.set(signer.clone(), expires_at);
+        Self::assert_permanent_signer(&signers)?;
+        Storage::set_backend_signers(&env, &signers);
+        Events::signer_added(&env, &signer, expires_at);
+
+        Ok(())
+    }
+
+    /// Revoke `signer` right away. The last signer without an expiry can't
+    /// be removed — that would eventually leave nobody to mark work complete
+    /// or resolve disputes.
+    pub fn remove_backend_signer(env: Env, signer: Address) -> Result<(), Error> {
+        Self::require_signer_admin(&env)?;
+
+        let mut signers = Storage::get_backend_signers(&env)?;
+        if signers.remove(signer.clone()).is_none() {
+            return Err(Error::NotAParty);
+        }
+        Self::assert_permanent_signer(&signers)?;
+
+        Storage::set_backend_signers(&env, &signers);
+        Events::signer_removed(&env, &signer);
+
+        Ok(())
+    }
+
+    /// Send future platform fees to `recipient` instead of the `backend`
+    /// address from initialize() — e.g. after that key was rotated out.
+    ///
+    /// `caller` must be the current fee recipient, or the platform's signer
+    /// admin (the one configured when the escrow was funded, or handed over
+    /// to by it). An admin the client and developer installed can't move
+    /// the platform's fee.
+    pub fn set_fee_recipient(env: Env, caller: Address, recipient: Address) -> Result<(), Error> {
+        if caller != Storage::get_backend(&env)? && Some(caller.clone()) != Storage::get_fee_admin(&env) {
+            return Err(Error::NotAParty);
+        }
+        caller.require_auth();
+
+        Storage::set_backend(&env, &recipient);
+        Events::fee_recipient_changed(&env, &recipient);
+
+        Ok(())
+    }
+
+    /// Replace config.signer_admin (None = the client and developer manage
+    /// the signer set together).
+    ///
+    /// While an admin is configured only that admin can hand over. With no
+    /// admin, the client and developer decide together.
+    pub fn set_signer_admin(env: Env, admin: Option<Address>) -> Result<(), Error> {
+        // In Draft the config is still part of the terms being agreed
+        if Storage::get_status(&env)? == EscrowStatus::Draft {
+            return Err(Error::EscrowInDraft);
+        }
+
+        let config = Storage::get_config(&env)?;
+        match &config.signer_admin {
+            Some(current) => {
+                current.require_auth();
+                // The platform handing over keeps control of its fee
+                Storage::set_fee_admin(&env, &admin);
+                Storage::remove_admin_removal_at(&env);
+            }
+            None => {
+                Storage::get_client(&env)?.require_auth();
+                Storage::get_developer(&env)?.require_auth();
+            }
+        }
+
+        Storage::set_config(&env, &EscrowConfig { signer_admin: admin.clone(), ..config });
+        Events::signer_admin_changed(&env, &admin);
+
+        Ok(())
+    }
+
+    /// Client and developer, together, start the clock on removing a signer
+    /// admin that has gone missing. Any action by the admin in the next
+    /// ADMIN_ABSENCE_SECS cancels it; after that, finalize_admin_removal().
+    pub fn request_admin_removal(env: Env) -> Result<(), Error> {
+        Storage::get_client(&env)?.require_auth();
+        Storage::get_developer(&env)?.require_auth();
+
+        if Storage::get_config(&env)?.signer_admin.is_none() {
+            return Err(Error::NothingPending);
+        }
+
+        let removable_at = env.ledger().timestamp()
+            .checked_add(ADMIN_ABSENCE_SECS)
+            .ok_or(Error::Overflow)?;
+        Storage::set_admin_removal_at(&env, removable_at);
+        Events::admin_removal_requested(&env, removable_at);
+
+        Ok(())
+    }
+
+    /// Remove a signer admin that stayed silent through the absence period.
+    /// The signer set then falls to the client and developer; the platform's
+    /// fee stays where it is.
+    pub fn finalize_admin_removal(env: Env) -> Result<(), Error> {
+        Storage::get_client(&env)?.require_auth();
+        Storage::get_developer(&env)?.require_auth();
+
+        let removable_at = Storage::get_admin_removal_at(&env).ok_or(Error::NothingPending)?;
+        if env.ledger().timestamp() < removable_at {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        let config = Storage::get_config(&env)?;
+        Storage::set_config(&env, &EscrowConfig { signer_admin: None, ..config });
+        Storage::remove_admin_removal_at(&env);
+        Events::signer_admin_changed(&env, &None);
+
+        Ok(())
+    }
+
+    // ─── Client transfer ──────────────────────────────────────────────────────
+
+    /// Client nominates `new_client` to take over the escrow (treasury move,
+    /// acquisition). Nothing changes until the new address
+    /// accept_client_transfer()s. Replaces any earlier nomination.
+    pub fn propose_client_transfer(env: Env, new_client: Address) -> Result<(), Error> {
+        Storage::get_client(&env)?.require_auth();
+
+        Self::assert_transferable(&env)?;
+
+        Storage::set_pending_client(&env, &new_client);
+        Events::client_transfer_proposed(&env, &new_client);
+
+        Ok(())
+    }
+
+    /// Nominated client takes over approval rights and every future refund.
+    ///
+    /// Nothing is re-keyed: open disputes, warranty claims and proposals the
+    /// old client made are tracked by party, not address, so they carry over
+    /// and whatever they settle to the client goes to the new address.
+    pub fn accept_client_transfer(env: Env) -> Result<(), Error> {
+        let new_client = Storage::get_pending_client(&env).ok_or(Error::NothingPending)?;
+        new_client.require_auth();
+
+        Self::assert_transferable(&env)?;
+
+        let old_client = Storage::get_client(&env)?;
+        Storage::set_client(&env, &new_client);
+        Storage::remove_pending_client(&env);
+        Events::client_transferred(&env, &old_client, &new_client);
+
+        Ok(())
+    }
+
+    // ─── Payout address ───────────────────────────────────────────────────────
+
+    /// Developer points future payouts at `address` — for every milestone, or
+    /// just `milestone_index` when given. Developer auth stays with the
+    /// original key.
+    ///
+    /// Takes effect after config.payout_delay_secs; until then payouts keep
+    /// going to the previous address. Every change emits a `payout` event so
+    /// the client can dispute() or pause work if the developer key is
+    /// compromised.
+    pub fn set_payout_address(
+        env:             Env,
+        address:         Address,
+        milestone_index: Option<u32>,
+    ) -> Result<(), Error> {
+        Storage::get_developer(&env)?.require_auth();
+
+        // Retention is still paid out during Warranty
+        Self::assert_transferable(&env)?;
+
+        let effective_at = env.ledger().timestamp()
+            .checked_add(Storage::get_config(&env)?.payout_delay_secs)
+            .ok_or(Error::Overflow)?;
+        let change = PayoutChange { address: address.clone(), effective_at };
+
+        match milestone_index {
+            Some(index) => {
+                let m = Storage::get_milestones(&env)?
+                    .get(index)
+                    .ok_or(Error::MilestoneNotFound)?;
+                // Settled, or delivered before a handover and still owed to
+                // the developer this one replaced — not this developer's to route
+                if Self::is_settled(&m.status) || m.payee.is_some() {
+                    return Err(Error::MilestoneAlreadyCompleted);
+                }
+                Storage::set_milestone_payout(&env, index, &change);
+            }
+            None => {
+                // A matured change becomes the base the new one replaces
+                Storage::set_payout_address(&env, &Self::payout_address(&env, None)?);
+                Storage::set_pending_payout(&env, &change);
+            }
+        }
+
+        Events::payout_changed(&env, milestone_index, &address, effective_at);
+
+        Ok(())
+    }
+
+    // ─── Replace developer ────────────────────────────────────────────────────
+
+    /// Client proposes handing the remaining work to `new_developer`.
+    ///
+    /// Takes effect once the new developer accept_replacement()s and the
+    /// current developer either consent_replacement()s or lets
+    /// config.replacement_timeout_secs pass (then anyone can
+    /// finalize_replacement()). Replaces any earlier proposal.
+    pub fn propose_replacement(env: Env, new_developer: Address) -> Result<(), Error> {
+        let client = Storage::get_client(&env)?;
+        client.require_auth();
+
+        Self::assert_active(&env)?;
+
+        Storage::set_replacement(&env, &PendingReplacement {
+            new_developer: new_developer.clone(),
+            proposed_at:   env.ledger().timestamp(),
+            accepted:      false,
+            consented:     false,
+        });
+        Events::replacement_proposed(&env, &new_developer);
+
+        Ok(())
+    }
+
+    /// The proposed new developer takes on the engagement.
+    pub fn accept_replacement(env: Env) -> Result<(), Error> {
+        let pending = Storage::get_replacement(&env).ok_or(Error::NothingPending)?;
+        pending.new_developer.require_auth();
+
+        Self::assert_active(&env)?;
+
+        let pending = PendingReplacement { accepted: true, ..pending };
+        Storage::set_replacement(&env, &pending);
+        Self::try_replace(&env, &pending)?;
+
+        Ok(())
+    }
+
+    /// The current developer agrees to hand over without waiting for the timeout.
+    pub fn consent_replacement(env: Env) -> Result<(), Error> {
+        Storage::get_developer(&env)?.require_auth();
+        let pending = Storage::get_replacement(&env).ok_or(Error::NothingPending)?;
+
+        Self::assert_active(&env)?;
+
+        let pending = PendingReplacement { consented: true, ..pending };
+        Storage::set_replacement(&env, &pending);
+        Self::try_replace(&env, &pending)?;
+
+        Ok(())
+    }
+
+    /// Complete an accepted replacement the old developer never consented to,
+    /// once config.replacement_timeout_secs have passed. Anyone can call this.
+    pub fn finalize_replacement(env: Env) -> Result<(), Error> {
+        let pending = Storage::get_replacement(&env).ok_or(Error::NothingPending)?;
+
+        Self::assert_active(&env)?;
+
+        if !pending.accepted {
+            return Err(Error::TermsNotAccepted);
+        }
+        if !Self::try_replace(&env, &pending)? {
+            return Err(Error::DeadlineNotReached);
+        }
+
+        Ok(())
+    }
+
+    // ─── Mutual termination ───────────────────────────────────────────────────
+
+    /// Propose ending the escrow with a negotiated split. The other party
+    /// must accept_termination().
+    ///
+    /// `developer_bps` is the developer's share of everything still held;
+    /// the client gets the rest. Replaces any earlier proposal. The proposal
+    /// only stands while the milestones stay exactly as they are now.
+    pub fn propose_termination(
+        env: Env,
+        proposer: Party,
+        developer_bps: u32,
+    ) -> Result<(), Error> {
+        Self::party_address(&env, &proposer)?.require_auth();
+        Self::assert_active(&env)?;
+
+        if developer_bps > 10_000 {
+            return Err(Error::InvalidAmount);
+        }
+
+        Storage::set_termination(&env, &PendingTermination {
+            proposer:        proposer.clone(),
+            developer_bps,
+            base_version:    Storage::get_terms_version(&env)?,
+            milestones_hash: Self::milestones_hash(&env)?,
+        });
+        Events::termination_proposed(&env, &proposer, developer_bps);
+
+        Ok(())
+    }
+
+    /// Accept the pending termination. Callable by the party that didn't
+    /// propose it, naming the `developer_bps` it agrees to. Fails as stale
+    /// if any milestone changed since the proposal.
+    ///
+    /// Every milestone that hasn't settled — Pending, in review or disputed —
+    /// ends Settled. The held funds are split as agreed (platform fee on the
+    /// developer's part, each milestone's share to its payout address),
+    /// retention goes to the developer, and the escrow closes as Terminated
+    /// in this one transaction. Milestones still owed to a replaced
+    /// developer (`payee`) are paid to them in full, outside the split.
+    pub fn accept_termination(env: Env, developer_bps: u32) -> Result<(), Error> {
+        let pending  = Storage::get_termination(&env).ok_or(Error::NothingPending)?;
+        let acceptor = Self::counterparty(&pending.proposer);
+        Self::party_address(&env, &acceptor)?.require_auth();
+
+        Self::assert_active(&env)?;
+
+        // Accept exactly the split that was offered, against the same state
+        if developer_bps != pending.developer_bps {
+            return Err(Error::TermsHashMismatch);
+        }
+        if pending.base_version != Storage::get_terms_version(&env)?
+            || pending.milestones_hash != Self::milestones_hash(&env)?
+        {
+            return Err(Error::StaleChangeOrder);
+        }
+
+        let mut milestones = Storage::get_milestones(&env)?;
+        let mut payouts    = Payouts::new(&env);
+        let mut settled: i128        = 0;
+        let mut developer_paid: i128 = 0;
+        let mut client_part: i128    = 0;
+        let mut fee_amount: i128     = 0;
+
+        for index in 0..milestones.len() {
+            let m = milestones.get(index).ok_or(Error::MilestoneNotFound)?;
+            if Self::is_settled(&m.status) {
+                continue;
+            }
+            if m.status == MilestoneStatus::PendingRelease {
+                Registry::untrack(&env, index)?;
+            }
+            settled = settled.checked_add(m.amount).ok_or(Error::Overflow)?;
+
+            // Unfunded milestones (just-in-time) hold nothing to split
+            if m.funded {
+                // Work owed to a replaced developer isn't part of the split
+                let (recipient, share) = match &m.payee {
+                    Some(payee) => (payee.clone(), m.amount),
+                    None        => (
+                        Self::payout_address(&env, Some(index))?,
+                        Self::bps_of(m.amount, pending.developer_bps),
+                    ),
+                };
+                let fee = Self::bps_of(share, PLATFORM_FEE_BPS);
+                payouts.add(&recipient, share - fee)?;
+
+                developer_paid += share - fee;
+                fee_amount     += fee;
+                client_part    += m.amount - share;
+            }
+
+            milestones.set(index, Milestone { status: MilestoneStatus::Settled, ..m });
+        }
+
+        payouts.add(&Storage::get_backend(&env)?, fee_amount)?;
+        payouts.add(&Storage::get_client(&env)?, client_part)?;
+        payouts.flush(&env)?;
+
+        Self::pay_retention(&env, &Self::payout_address(&env, None)?)?;
+
+        Storage::set_milestones(&env, &milestones);
+        let released = Storage::get_released_amount(&env)?;
+        Storage::set_released_amount(&env, released + settled);
+        Storage::remove_termination(&env);
+        Storage::set_status(&env, EscrowStatus::Terminated);
+
+        Events::terminated(&env, developer_paid, client_part);
+
+        Ok(())
+    }
+
+    /// Drop the pending termination. Either party can do this.
+    pub fn withdraw_termination(env: Env, party: Party) -> Result<(), Error> {
+        Self::party_address(&env, &party)?.require_auth();
+
+        if Storage::get_termination(&env).is_none() {
+            return Err(Error::NothingPending);
+        }
+        Storage::remove_termination(&env);
+
+        Events::termination_withdrawn(&env, &party);
+
+        Ok(())
+    }
+
+    // ─── Views ────────────────────────────────────────────────────────────────
+
+    /// Get the full milestone list.
+    pub fn get_milestones(env: Env) -> Result<Vec<Milestone>, Error> {
+        Storage::get_milestones(&env)
+    }
+
+    /// Get a single milestone.
+    pub fn get_milestone(env: Env, index: u32) -> Result<Milestone, Error> {
+        let milestones = Storage::get_milestones(&env)?;
+        milestones.get(index).ok_or(Error::MilestoneNotFound)
+    }
+
+    /// Get overall escrow status.
+    pub fn get_status(env: Env) -> Result<EscrowStatus, Error> {
+        Storage::get_status(&env)
+    }
+
+    /// Get total and released amounts. `released` includes any advance.
+    pub fn get_balance(env: Env) -> Result<(i128, i128, i128), Error> {
+        let total    = Storage::get_total_amount(&env)?;
+        let released = Storage::get_released_amount(&env)?;
+        let remaining = total.checked_sub(released).ok_or(Error::Overflow)?;
+        Ok((total, released, remaining))
+    }
+
+    /// Retention currently held, and when the warranty period ends
+    /// (None until every milestone has settled).
+    pub fn get_retention(env: Env) -> Result<(i128, Option<u64>), Error> {
+        let retained = Storage::get_retained_amount(&env)?;
+        Ok((retained, Storage::get_warranty_ends_at(&env)))
+    }
+
+    /// When a requested cancellation takes effect, if one was requested.
+    pub fn get_cancel_at(env: Env) -> Option<u64> {
+        Storage::get_cancel_at(&env)
+    }
+
+    /// Backend signers and their expiry (0 = never).
+    pub fn get_backend_signers(env: Env) -> Result<Map<Address, u64>, Error> {
+        Storage::get_backend_signers(&env)
+    }
+
+    /// Client address nominated by propose_client_transfer(), if any.
+    pub fn get_pending_client(env: Env) -> Option<Address> {
+        Storage::get_pending_client(&env)
+    }
+
+    /// Where the developer's share of a payout goes right now — for
+    /// `milestone_index` if given, otherwise the default.
+    pub fn get_payout_address(env: Env, milestone_index: Option<u32>) -> Result<Address, Error> {
+        if let Some(index) = milestone_index {
+            let m = Storage::get_milestones(&env)?
+                .get(index)
+                .ok_or(Error::MilestoneNotFound)?;
+            if let Some(payee) = m.payee {
+                return Ok(payee);
+            }
+        }
+        Self::payout_address(&env, milestone_index)
+    }
+
+    /// Pending developer replacement, if any.
+    pub fn get_replacement(env: Env) -> Option<PendingReplacement> {
+        Storage::get_replacement(&env)
+    }
+
+    /// Pending termination proposal, if any.
+    pub fn get_termination(env: Env) -> Option<PendingTermination> {
+        Storage::get_termination(&env)
+    }
+
+    /// Open warranty claim, if any.
+    pub fn get_warranty_claim(env: Env) -> Option<WarrantyClaim> {
+        Storage::get_warranty_claim(&env)
+    }
+
+    /// External id, statement-of-work hash, title and repo set at initialize().
+    pub fn get_metadata(env: Env) -> Result<EscrowMetadata, Error> {
+        Storage::get_metadata(&env)
+    }
+
+    /// Terms currently on the table (or, once funded, the original terms
+    /// as amended by change orders).
+    pub fn get_terms(env: Env) -> Result<Terms, Error> {
+        Self::current_terms(&env)
+    }
+
+    /// sha256 over the XDR of get_terms() — what accept_terms() and fund() expect.
+    pub fn get_terms_hash(env: Env) -> Result<BytesN<32>, Error> {
+        Self::terms_hash(&env)
+    }
+
+    /// Open terms proposal while the escrow is a Draft.
+    pub fn get_proposal(env: Env) -> Option<TermsProposal> {
+        Storage::get_proposal(&env)
+    }
+
+    /// Advance paid to the developer when the escrow was funded (before fees).
+    pub fn get_advance(env: Env) -> Result<i128, Error> {
+        Storage::get_advance_amount(&env)
+    }
+
+    /// What a milestone still pays out (before fees).
+    /// 0 for milestones that already settled.
+    pub fn get_milestone_amount(env: Env, index: u32) -> Result<i128, Error> {
+        let milestones = Storage::get_milestones(&env)?;
+        let milestone  = milestones.get(index).ok_or(Error::MilestoneNotFound)?;
+        if Self::is_settled(&milestone.status) {
+            return Ok(0);
+        }
+        Ok(milestone.amount)
+    }
+
+    /// How much of the outstanding work is actually backed by funds.
+    pub fn get_funding(env: Env) -> Result<FundingCoverage, Error> {
+        let milestones = Storage::get_milestones(&env)?;
+        let mut coverage = FundingCoverage {
+            funded_amount:       0,
+            unfunded_amount:     0,
+            funded_milestones:   0,
+            unfunded_milestones: 0,
+            next_unfunded:       None,
+        };
+
+        for (index, m) in milestones.iter().enumerate() {
+            if Self::is_settled(&m.status) {
+                continue;
+            }
+            if m.funded {
+                coverage.funded_amount     += m.amount;
+                coverage.funded_milestones += 1;
+            } else {
+                coverage.unfunded_amount     += m.amount;
+                coverage.unfunded_milestones += 1;
+                if coverage.next_unfunded.is_none() {
+                    coverage.next_unfunded = Some(index as u32);
+                }
+            }
+        }
+
+        Ok(coverage)
+    }
+
+    /// Current terms version — bumped by every accepted change order
+    /// and every renegotiated total.
+    pub fn get_terms_version(env: Env) -> Result<u32, Error> {
+        Storage::get_terms_version(&env)
+    }
+
+    /// The change order awaiting acceptance, if any.
+    pub fn get_change_order(env: Env) -> Option<PendingChangeOrder> {
+        Storage::get_change_order(&env)
+    }
+
+    /// How many seconds remain in the dispute window for a milestone.
+    pub fn time_until_auto_release(env: Env, milestone_index: u32) -> Result<u64, Error> {
+        let milestones      = Storage::get_milestones(&env)?;
+        let milestone       = milestones.get(milestone_index).ok_or(Error::MilestoneNotFound)?;
+
+        if milestone.status != MilestoneStatus::PendingRelease {
+            return Ok(0);
+        }
+
+        let completed_at = milestone.completed_at.ok_or(Error::MilestoneNotCompleted)?;
+        let now          = env.ledger().timestamp();
+        let deadline     = match Self::auto_release_at(&env, completed_at)? {
+            Some(deadline) => deadline,
+            None           => return Ok(0),
+        };
+
+        if now >= deadline {
+            Ok(0)
+        } else {
+            Ok(deadline - now)
+        }
+    }
+
+    // ─── Internal ─────────────────────────────────────────────────────────────
+
+    fn assert_active(env: &Env) -> Result<(), Error> {
+        match Storage::get_status(env)? {
+            EscrowStatus::Active => Ok(()),
+            EscrowStatus::Draft      => Err(Error::EscrowInDraft),
+            EscrowStatus::Completed  => Err(Error::EscrowCompleted),
+            EscrowStatus::Cancelled  => Err(Error::EscrowCancelled),
+            EscrowStatus::Warranty   => Err(Error::EscrowInWarranty),
+            EscrowStatus::Expired    => Err(Error::EscrowExpired),
+            EscrowStatus::Withdrawn  => Err(Error::EscrowWithdrawn),
+            EscrowStatus::Terminated => Err(Error::EscrowTerminated),
+        }
+    }
+
+    /// `signer` must be in the backend signer set and not expired.
+    fn require_backend(env: &Env, signer: &Address) -> Result<(), Error> {
+        let expires_at = Storage::get_backend_signers(env)?
+            .get(signer.clone())
+            .ok_or(Error::NotAParty)?;
+        if expires_at != 0 && env.ledger().timestamp() >= expires_at {
+            return Err(Error::NotAParty);
+        }
+        signer.require_auth();
+
+        Ok(())
+    }
+
+    /// The signer set must keep at least one key that never expires.
+    fn assert_permanent_signer(signers: &Map<Address, u64>) -> Result<(), Error> {
+        if !signers.values().iter().any(|expires_at| expires_at == 0) {
+            return Err(Error::InvalidConfig);
+        }
+        Ok(())
+    }
+
+    fn require_signer_admin(env: &Env) -> Result<(), Error> {
+        match Storage::get_config(env)?.signer_admin {
+            Some(admin) => {
+                admin.require_auth();
+                // The admin is evidently still around
+                Storage::remove_admin_removal_at(env);
+            }
+            None => {
+                Storage::get_client(env)?.require_auth();
+                Storage::get_developer(env)?.require_auth();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Once config.expires_at has passed nothing new may start — otherwise
+    /// fresh review windows could hold off expire() forever.
+    fn assert_not_expired(env: &Env) -> Result<(), Error> {
+        let expires_at = Storage::get_config(env)?.expires_at;
+        if expires_at != 0 && env.ledger().timestamp() >= expires_at {
+            return Err(Error::EscrowExpired);
+        }
+        Ok(())
+    }
+
+    /// Party changes are allowed while money is still held: Active, or
+    /// Warranty with retention outstanding.
+    fn assert_transferable(env: &Env) -> Result<(), Error> {
+        if Storage::get_status(env)? == EscrowStatus::Warranty {
+            return Ok(());
+        }
+        Self::assert_active(env)
+    }
+
+    fn complete_milestone(env: &Env, milestone_index: u32, pr_url: &String) -> Result<(), Error> {
+        let mut milestones = Storage::get_milestones(env)?;
+        let milestone = milestones
+            .get(milestone_index)
+            .ok_or(Error::MilestoneNotFound)?;
+
+        if milestone.status != MilestoneStatus::Pending {
+            return Err(Error::MilestoneAlreadyCompleted);
+        }
+        if !milestone.funded {
+            return Err(Error::MilestoneNotFunded);
+        }
+
+        // Past expiry only expire() can settle — no new review windows
+        Self::assert_not_expired(env)?;
+
+        // Winding down: work finished after the notice period doesn't count
+        if let Some(cancel_at) = Storage::get_cancel_at(env) {
+            if env.ledger().timestamp() >= cancel_at {
+                return Err(Error::EscrowCancelled);
+            }
+        }
+
+        // Update milestone state
+        let completed_at = env.ledger().timestamp();
+        let updated = Milestone {
+            status:       MilestoneStatus::PendingRelease,
+            pr_url:       Some(pr_url.clone()),
+            completed_at: Some(completed_at),
+            ..milestone
+        };
+
+        milestones.set(milestone_index, updated);
+        Storage::set_milestones(env, &milestones);
+
+        // Let keepers know when this one becomes auto-releasable
+        if let Some(deadline) = Self::auto_release_at(env, completed_at)? {
+            Registry::track(env, milestone_index, deadline)?;
+        }
+
+        Events::milestone_completed(env, milestone_index, pr_url, completed_at);
+
+        Ok(())
+    }
+
+    /// BPS must sum to exactly 10_000, or to 0 when every milestone is fixed.
+    fn validate_bps(milestones: &Vec<Milestone>) -> Result<(), Error> {
+        let total_bps: u32 = milestones.iter().map(|m| m.bps).sum();
+        if total_bps != 10_000 && total_bps != 0 {
+            return Err(Error::InvalidMilestoneBps);
+        }
+        Ok(())
+    }
+
+    /// Validate a set of proposed terms and work out every milestone's
+    /// exact payout. Shared by initialize() and counter_propose().
+    fn prepare_terms(
+        env: &Env,
+        total_amount: i128,
+        milestones: Vec<Milestone>,
+        config: &EscrowConfig,
+    ) -> Result<Vec<Milestone>, Error> {
+        // Validate inputs
+        if total_amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if milestones.is_empty() {
+            return Err(Error::NoMilestones);
+        }
+        if milestones.len() > MAX_MILESTONES {
+            return Err(Error::TooManyMilestones);
+        }
+
+        // Validate milestone BPS sums to exactly 10000 (or 0 if all are fixed)
+        Self::validate_bps(&milestones)?;
+        for milestone in milestones.iter() {
+            Self::validate_new_milestone(&milestone)?;
+            Self::validate_share(&milestone)?;
+        }
+
+        // Optional advance comes off the top; milestones split the rest
+        let advance = Self::advance_of(config, total_amount)?;
+
+        // Work out every milestone's exact payout
+        let mut milestones = milestones;
+        Self::allocate_amounts(&mut milestones, total_amount, advance, false)?;
+        for index in 0..milestones.len() {
+            let m = milestones.get(index).ok_or(Error::MilestoneNotFound)?;
+            milestones.set(index, Milestone { funded: false, ..m });
+        }
+
+        if config.keeper_bps > MAX_KEEPER_BPS {
+            return Err(Error::InvalidKeeperBps);
+        }
+        if config.kill_fee_bps > 10_000 {
+            return Err(Error::InvalidConfig);
+        }
+        if config.retention_bps > MAX_RETENTION_BPS {
+            return Err(Error::InvalidConfig);
+        }
+        if let LatePolicy::Penalty(bps_per_day) = config.late_policy {
+            if bps_per_day > 10_000 {
+                return Err(Error::InvalidConfig);
+            }
+        }
+        if config.expires_at != 0 && config.expires_at <= env.ledger().timestamp() {
+            return Err(Error::InvalidConfig);
+        }
+
+        Ok(milestones)
+    }
+
+    fn assert_draft(env: &Env) -> Result<(), Error> {
+        if Storage::get_status(env)? != EscrowStatus::Draft {
+            return Err(Error::NotADraft);
+        }
+        Ok(())
+    }
+
+    /// The terms as currently stored — what get_terms_hash() hashes.
+    fn current_terms(env: &Env) -> Result<Terms, Error> {
+        Ok(Terms {
+            client:              Storage::get_client(env)?,
+            developer:           Storage::get_developer(env)?,
+            backend:             Storage::get_backend(env)?,
+            token:               Storage::get_token(env)?,
+            total_amount:        Storage::get_total_amount(env)?,
+            milestones:          Storage::get_milestones(env)?,
+            dispute_window_secs: Storage::get_dispute_window(env)?,
+            config:              Storage::get_config(env)?,
+            metadata:            Storage::get_metadata(env)?,
+        })
+    }
+
+    fn milestones_hash(env: &Env) -> Result<BytesN<32>, Error> {
+        let xdr = Storage::get_milestones(env)?.to_xdr(env);
+        Ok(env.crypto().sha256(&xdr).to_bytes())
+    }
+
+    fn terms_hash(env: &Env) -> Result<BytesN<32>, Error> {
+        let xdr = Self::current_terms(env)?.to_xdr(env);
+        Ok(env.crypto().sha256(&xdr).to_bytes())
+    }
+
+    /// Replace the open proposal with the stored terms, proposed by `proposer`.
+    fn open_proposal(env: &Env, proposer: Party) -> Result<(), Error> {
+        let ttl = Storage::get_config(env)?.proposal_ttl_secs;
+        let expires_at = if ttl == 0 {
+            0
+        } else {
+            env.ledger().timestamp().checked_add(ttl).ok_or(Error::Overflow)?
+        };
+
+        let terms_hash = Self::terms_hash(env)?;
+        Storage::set_proposal(env, &TermsProposal {
+            proposer: proposer.clone(),
+            terms_hash: terms_hash.clone(),
+            expires_at,
+            accepted: false,
+        });
+        Events::terms_proposed(env, &proposer, &terms_hash, expires_at);
+
+        Ok(())
+    }
+
+    /// The open, unexpired proposal — provided `terms_hash` is its hash.
+    fn open_proposal_matching(env: &Env, terms_hash: &BytesN<32>) -> Result<TermsProposal, Error> {
+        Self::assert_draft(env)?;
+
+        let proposal = Storage::get_proposal(env).ok_or(Error::NotADraft)?;
+        if proposal.expires_at != 0 && env.ledger().timestamp() >= proposal.expires_at {
+            return Err(Error::ProposalExpired);
+        }
+        if proposal.terms_hash != *terms_hash {
+            return Err(Error::TermsHashMismatch);
+        }
+
+        Ok(proposal)
+    }
+
+    /// Resolve the configured advance against `total`. Must leave a
+    /// non-zero remainder for the milestones.
+    fn advance_of(config: &EscrowConfig, total: i128) -> Result<i128, Error> {
+        if config.advance_bps > 0 && config.advance_amount != 0 {
+            return Err(Error::InvalidConfig);
+        }
+        let advance = if config.advance_bps > 0 {
+            Self::bps_of(total, config.advance_bps)
+        } else {
+            config.advance_amount
+        };
+        if advance < 0 || advance >= total {
+            return Err(Error::InvalidConfig);
+        }
+        Ok(advance)
+    }
+
+    /// A new milestone is either bps-based (amount 0, filled in by
+    /// allocate_amounts) or fixed (bps 0, amount > 0) — never both.
+    /// A milestone coming in from outside (terms, change orders) must be
+    /// fresh — no progress, and no payee the contract didn't set itself.
+    fn validate_new_milestone(milestone: &Milestone) -> Result<(), Error> {
+        if milestone.status != MilestoneStatus::Pending
+            || milestone.pr_url.is_some()
+            || milestone.completed_at.is_some()
+            || milestone.change_requests != 0
+            || milestone.payee.is_some()
+        {
+            return Err(Error::MilestoneNotPending);
+        }
+        Ok(())
+    }
+
+    fn validate_share(milestone: &Milestone) -> Result<(), Error> {
+        let bps_based = milestone.bps > 0 && milestone.amount == 0;
+        let fixed     = milestone.bps == 0 && milestone.amount > 0;
+        if !bps_based && !fixed {
+            return Err(Error::InvalidMilestoneAmount);
+        }
+        Ok(())
+    }
+
+    /// Recompute the stored amount of bps milestones so that, together with
+    /// everything else still in the contract, amounts sum to `total - released`.
+    ///
+    /// Only unsettled bps milestones are touched — Pending ones only when
+    /// `pending_only` (change orders leave in-flight work alone). They split
+    /// what's left by bps; the last one takes the rounding dust.
+    fn allocate_amounts(
+        milestones: &mut Vec<Milestone>,
+        total: i128,
+        released: i128,
+        pending_only: bool,
+    ) -> Result<(), Error> {
+        let eligible = |m: &Milestone| {
+            m.bps > 0
+                && if pending_only {
+                    m.status == MilestoneStatus::Pending
+                } else {
+                    !Self::is_settled(&m.status)
+                }
+        };
+
+        let mut pool = total.checked_sub(released).ok_or(Error::Overflow)?;
+        let mut pool_bps: u32 = 0;
+        let mut last: Option<u32> = None;
+
+        for (index, m) in milestones.iter().enumerate() {
+            if eligible(&m) {
+                pool_bps += m.bps;
+                last = Some(index as u32);
+            } else if !Self::is_settled(&m.status) {
+                pool = pool.checked_sub(m.amount).ok_or(Error::Overflow)?;
+            }
+        }
+
+        if pool < 0 {
+            return Err(Error::InvalidMilestoneAmount);
+        }
+        let last = match last {
+            Some(last) => last,
+            // Nothing to absorb the pool — it must already be exactly spoken for
+            None if pool == 0 => return Ok(()),
+            None => return Err(Error::InvalidMilestoneAmount),
+        };
+
+        let mut allocated: i128 = 0;
+        for index in 0..milestones.len() {
+            let m = milestones.get(index).ok_or(Error::MilestoneNotFound)?;
+            if !eligible(&m) {
+                continue;
+            }
+
+            let amount = if index == last {
+                pool - allocated
+            } else {
+                pool * m.bps as i128 / pool_bps as i128
+            };
+            allocated += amount;
+            milestones.set(index, Milestone { amount, ..m });
+        }
+
+        Ok(())
+    }
+
+    /// Tokens the contract holds for open milestones: every unsettled,
+    /// funded milestone's amount.
+    fn held_amount(milestones: &Vec<Milestone>) -> i128 {
+        milestones
+            .iter()
+            .filter(|m| m.funded && !Self::is_settled(&m.status))
+            .map(|m| m.amount)
+            .sum()
+    }
+
+    /// Just-in-time mode: fund Pending milestones in index order until
+    /// `funded_ahead` open milestones are funded. One transfer from the client.
+    fn top_up_funding(env: &Env) -> Result<(), Error> {
+        let config = Storage::get_config(env)?;
+        if config.funding_mode != FundingMode::JustInTime || config.funded_ahead == 0 {
+            return Ok(());
+        }
+        // Nothing more to fund once the escrow is over, winding down or expired
+        if Storage::get_status(env)? != EscrowStatus::Active
+            || Storage::get_cancel_at(env).is_some()
+            || Self::assert_not_expired(env).is_err()
+        {
+            return Ok(());
+        }
+
+        let mut milestones = Storage::get_milestones(env)?;
+        let mut covered = milestones
+            .iter()
+            .filter(|m| m.funded && !Self::is_settled(&m.status))
+            .count() as u32;
+        let mut amount: i128 = 0;
+
+        for index in 0..milestones.len() {
+            if covered >= config.funded_ahead {
+                break;
+            }
+            let m = milestones.get(index).ok_or(Error::MilestoneNotFound)?;
+            if m.status != MilestoneStatus::Pending || m.funded {
+                continue;
+            }
+
+            amount = amount.checked_add(m.amount).ok_or(Error::Overflow)?;
+            covered += 1;
+            Events::milestone_funded(env, index, m.amount);
+            milestones.set(index, Milestone { funded: true, ..m });
+        }
+
+        if amount > 0 {
+            let token  = Storage::get_token(env)?;
+            let client = Storage::get_client(env)?;
+            token::Client::new(env, &token).transfer(
+                &client,
+                &env.current_contract_address(),
+                &amount,
+            );
+            Storage::set_milestones(env, &milestones);
+        }
+
+        Ok(())
+    }
+
+    /// Refund everything still held to the client and mark the escrow
+    /// Cancelled. Shared by cancel() and finalize_cancel().
+    fn close_out(env: &Env, client: &Address) -> Result<(), Error> {
+        let milestones = Storage::get_milestones(env)?;
 
         // Ensure no milestone is in a "limbo" state
         for milestone in milestones.iter() {
@@ -355,91 +2340,246 @@ impl StreamEscrow {
             }
         }
 
-        // Refund unreleased amount
-        let total_amount    = Storage::get_total_amount(&env)?;
-        let released_amount = Storage::get_released_amount(&env)?;
-        let remaining       = total_amount
-            .checked_sub(released_amount)
-            .ok_or(Error::Overflow)?;
+        // Refund what's actually held — unfunded milestones were never paid in
+        let remaining = Self::held_amount(&milestones);
 
-        if remaining > 0 {
-            let token = Storage::get_token(&env)?;
-            let token_client = token::Client::new(&env, &token);
-            token_client.transfer(
-                &env.current_contract_address(),
-                &client,
-                &remaining,
-            );
-        }
+        // Kill fee compensates the developer for reserved time, fee applied
+        let developer  = Self::payout_address(env, None)?;
+        let kill_fee   = Self::bps_of(remaining, Storage::get_config(env)?.kill_fee_bps);
+        let fee_amount = Self::bps_of(kill_fee, PLATFORM_FEE_BPS);
+        let refund     = remaining - kill_fee;
 
-        Storage::set_status(&env, EscrowStatus::Cancelled);
-        Events::cancelled(&env, &client, remaining);
+        let mut payouts = Payouts::new(env);
+        payouts.add(&developer, kill_fee - fee_amount)?;
+        payouts.add(&Storage::get_backend(env)?, fee_amount)?;
+        payouts.add(client, refund)?;
+        payouts.flush(env)?;
+
+        // Retention was earned on accepted work — no warranty without a delivery
+        Self::pay_retention(env, &developer)?;
+
+        Storage::set_status(env, EscrowStatus::Cancelled);
+        Events::cancelled(env, client, refund, kill_fee - fee_amount);
 
         Ok(())
     }
 
-    // ─── Views ────────────────────────────────────────────────────────────────
+    /// Current developer's payout address, for `milestone_index` if given.
+    ///
+    /// A milestone override wins once in force, then the latest default
+    /// change in force, then the developer address itself.
+    fn payout_address(env: &Env, milestone_index: Option<u32>) -> Result<Address, Error> {
+        let now = env.ledger().timestamp();
+
+        if let Some(index) = milestone_index {
+            if let Some(change) = Storage::get_milestone_payout(env, index) {
+                if now >= change.effective_at {
+                    return Ok(change.address);
+                }
+            }
+        }
 
-    /// Get the full milestone list.
-    pub fn get_milestones(env: Env) -> Result<Vec<Milestone>, Error> {
-        Storage::get_milestones(&env)
+        if let Some(change) = Storage::get_pending_payout(env) {
+            if now >= change.effective_at {
+                return Ok(change.address);
+            }
+        }
+
+        match Storage::get_payout_address(env) {
+            Some(address) => Ok(address),
+            None          => Storage::get_developer(env),
+        }
     }
 
-    /// Get a single milestone.
-    pub fn get_milestone(env: Env, index: u32) -> Result<Milestone, Error> {
-        let milestones = Storage::get_milestones(&env)?;
-        milestones.get(index).ok_or(Error::MilestoneNotFound)
+    /// Drop every payout address the current developer set. Called when the
+    /// developer changes.
+    fn clear_payout_addresses(env: &Env, milestone_count: u32) {
+        Storage::remove_payout_address(env);
+        Storage::remove_pending_payout(env);
+        for index in 0..milestone_count {
+            Storage::remove_milestone_payout(env, index);
+        }
     }
 
-    /// Get overall escrow status.
-    pub fn get_status(env: Env) -> Result<EscrowStatus, Error> {
-        Storage::get_status(&env)
+    /// Hand the escrow to the new developer if the replacement is ready:
+    /// accepted, and either consented or past the timeout. Returns whether
+    /// it happened.
+    ///
+    /// Released work stays with the old developer, and so does work already
+    /// in review or dispute (pinned through `payee`). Pending milestones pay
+    /// the new developer. Retention earned so far is paid out to the old
+    /// developer, and open change-order / termination proposals are dropped.
+    fn try_replace(env: &Env, pending: &PendingReplacement) -> Result<bool, Error> {
+        if !pending.accepted {
+            return Ok(false);
+        }
+        if !pending.consented {
+            let timeout = Storage::get_config(env)?.replacement_timeout_secs;
+            let ready_at = pending.proposed_at.checked_add(timeout).ok_or(Error::Overflow)?;
+            if timeout == 0 || env.ledger().timestamp() < ready_at {
+                return Ok(false);
+            }
+        }
+
+        let old_developer  = Storage::get_developer(env)?;
+        let mut milestones = Storage::get_milestones(env)?;
+        for index in 0..milestones.len() {
+            let m = milestones.get(index).ok_or(Error::MilestoneNotFound)?;
+            let in_flight = matches!(
+                m.status,
+                MilestoneStatus::PendingRelease | MilestoneStatus::Disputed
+            );
+            if in_flight && m.payee.is_none() {
+                let payee = Self::payout_address(env, Some(index))?;
+                milestones.set(index, Milestone { payee: Some(payee), ..m });
+            }
+        }
+        Storage::set_milestones(env, &milestones);
+
+        Self::pay_retention(env, &Self::payout_address(env, None)?)?;
+        Self::clear_payout_addresses(env, milestones.len());
+
+        Storage::set_developer(env, &pending.new_developer);
+        Storage::remove_replacement(env);
+        Storage::remove_change_order(env);
+        Storage::remove_termination(env);
+
+        Events::developer_replaced(env, &old_developer, &pending.new_developer);
+
+        Ok(true)
     }
 
-    /// Get total and released amounts.
-    pub fn get_balance(env: Env) -> Result<(i128, i128, i128), Error> {
-        let total    = Storage::get_total_amount(&env)?;
-        let released = Storage::get_released_amount(&env)?;
-        let remaining = total.checked_sub(released).ok_or(Error::Overflow)?;
-        Ok((total, released, remaining))
+    /// Send the whole retention to `recipient` (fee was already taken on
+    /// release) and zero it.
+    fn pay_retention(env: &Env, recipient: &Address) -> Result<(), Error> {
+        let retained = Storage::get_retained_amount(env)?;
+        if retained == 0 {
+            return Ok(());
+        }
+
+        let mut payouts = Payouts::new(env);
+        payouts.add(recipient, retained)?;
+        payouts.flush(env)?;
+
+        Storage::set_retained_amount(env, 0);
+        Events::retention_paid(env, recipient, retained);
+
+        Ok(())
     }
 
-    /// How many seconds remain in the dispute window for a milestone.
-    pub fn time_until_auto_release(env: Env, milestone_index: u32) -> Result<u64, Error> {
-        let milestones      = Storage::get_milestones(&env)?;
-        let milestone       = milestones.get(milestone_index).ok_or(Error::MilestoneNotFound)?;
-        let dispute_window  = Storage::get_dispute_window(&env)?;
+    /// Terminal status once nothing is left to pay out: Completed, or
+    /// Withdrawn if the developer stepped away with developer_withdraw().
+    fn finish(env: &Env) {
+        if Storage::is_developer_withdrawn(env) {
+            Storage::set_status(env, EscrowStatus::Withdrawn);
+            Events::withdrawn(env);
+        } else {
+            Storage::set_status(env, EscrowStatus::Completed);
+            Events::completed(env);
+        }
+    }
 
-        if milestone.status != MilestoneStatus::PendingRelease || dispute_window == 0 {
-            return Ok(0);
+    /// Terminal milestone states — their amount has left the contract.
+    fn is_settled(status: &MilestoneStatus) -> bool {
+        matches!(
+            status,
+            MilestoneStatus::Released
+                | MilestoneStatus::Refunded
+                | MilestoneStatus::Removed
+                | MilestoneStatus::Settled
+        )
+    }
+
+    fn bps_of(amount: i128, bps: u32) -> i128 {
+        amount * bps as i128 / 10_000
+    }
+
+    fn assert_auto_releasable(env: &Env, milestone: &Milestone) -> Result<(), Error> {
+        if milestone.status != MilestoneStatus::PendingRelease {
+            return Err(Error::MilestoneNotPendingRelease);
         }
 
         let completed_at = milestone.completed_at.ok_or(Error::MilestoneNotCompleted)?;
+        let deadline     = Self::auto_release_at(env, completed_at)?
+            .ok_or(Error::ManualApprovalRequired)?;
         let now          = env.ledger().timestamp();
-        let deadline     = completed_at.checked_add(dispute_window).ok_or(Error::Overflow)?;
 
-        if now >= deadline {
-            Ok(0)
+        if now < deadline {
+            return if Storage::get_dispute_window(env)? > 0 {
+                Err(Error::DisputeWindowOpen)
+            } else {
+                Err(Error::ApprovalDeadlineOpen)
+            };
+        }
+
+        Ok(())
+    }
+
+    /// End of the client's review for a milestone completed at `completed_at`:
+    /// the dispute window, or the approval deadline in manual mode.
+    /// None = manual mode without a deadline — the review never closes.
+    fn review_deadline(env: &Env, completed_at: u64) -> Result<Option<u64>, Error> {
+        let dispute_window = Storage::get_dispute_window(env)?;
+        let review_secs = if dispute_window > 0 {
+            dispute_window
         } else {
-            Ok(deadline - now)
+            Storage::get_config(env)?.approval_deadline_secs
+        };
+
+        if review_secs == 0 {
+            return Ok(None);
         }
+
+        completed_at
+            .checked_add(review_secs)
+            .map(Some)
+            .ok_or(Error::Overflow)
     }
 
-    // ─── Internal ─────────────────────────────────────────────────────────────
+    /// When a milestone completed at `completed_at` becomes releasable
+    /// without the client: the end of the dispute window or, in manual mode
+    /// with ApprovalTimeout::AutoRelease, the approval deadline.
+    /// None = only approve() or arbitration can release it.
+    fn auto_release_at(env: &Env, completed_at: u64) -> Result<Option<u64>, Error> {
+        if Storage::get_dispute_window(env)? == 0
+            && Storage::get_config(env)?.approval_timeout != ApprovalTimeout::AutoRelease
+        {
+            return Ok(None);
+        }
 
-    fn assert_active(env: &Env) -> Result<(), Error> {
-        match Storage::get_status(env)? {
-            EscrowStatus::Active => Ok(()),
-            EscrowStatus::Completed  => Err(Error::EscrowCompleted),
-            EscrowStatus::Cancelled  => Err(Error::EscrowCancelled),
+        Self::review_deadline(env, completed_at)
+    }
+
+    fn require_keeper_auth(keeper: &Option<Address>) {
+        if let Some(keeper) = keeper {
+            keeper.require_auth();
         }
     }
 
-    fn milestone_amount(total: i128, bps: u32) -> i128 {
-        total * bps as i128 / 10_000
+    fn release_milestone(
+        env: &Env,
+        milestone_index: u32,
+        keeper: &Option<Address>,
+        auto: bool,
+    ) -> Result<(), Error> {
+        let mut payouts = Payouts::new(env);
+        Self::queue_release(env, milestone_index, keeper, auto, &mut payouts)?;
+        payouts.flush(env)?;
+
+        Self::check_completion(env)?;
+
+        Ok(())
     }
 
-    fn release_milestone(env: &Env, milestone_index: u32, auto: bool) -> Result<(), Error> {
+    /// Mark a milestone Released and queue its developer share, fee and
+    /// keeper reward. The caller flushes the payouts and runs check_completion().
+    fn queue_release(
+        env: &Env,
+        milestone_index: u32,
+        keeper: &Option<Address>,
+        auto: bool,
+        payouts: &mut Payouts,
+    ) -> Result<(), Error> {
         let mut milestones = Storage::get_milestones(env)?;
         let milestone = milestones
             .get(milestone_index)
@@ -449,29 +2589,52 @@ impl StreamEscrow {
             return Err(Error::MilestoneNotPendingRelease);
         }
 
-        // Calculate this milestone's share
-        let total_amount  = Storage::get_total_amount(env)?;
-        let release_amount = Self::milestone_amount(total_amount, milestone.bps);
+        // This milestone's exact share, fixed at init / last change of terms
+        let release_amount = milestone.amount;
+
+        // Late delivery: the penalty goes back to the client, the rest is earned
+        let config  = Storage::get_config(env)?;
+        let penalty = Self::late_penalty(env, milestone_index, &milestone, &config.late_policy)?;
+        let earned  = release_amount - penalty;
+        payouts.add(&Storage::get_client(env)?, penalty)?;
+
+        // Platform fee: 1%. Retention stays in the contract until the warranty is over —
+        // except on work owed to a replaced developer, whose retention was
+        // settled at the handover and whom a later warranty can't reach
+        let fee_amount = Self::bps_of(earned, PLATFORM_FEE_BPS);
+        let retained   = if milestone.payee.is_some() {
+            0
+        } else {
+            Self::bps_of(earned, config.retention_bps)
+        };
+        let dev_amount = earned - fee_amount - retained;
 
-        // Platform fee: 1%
-        let fee_amount = release_amount / 100;
-        let dev_amount = release_amount - fee_amount;
+        // Work finished before a developer handover stays owed to the old developer
+        let developer = match &milestone.payee {
+            Some(payee) => payee.clone(),
+            None        => Self::payout_address(env, Some(milestone_index))?,
+        };
+        let backend   = Storage::get_backend(env)?;  // fee goes to backend/platform
+
+        // Keeper reward comes out of the platform's cut, never the developer's
+        let mut platform_amount = fee_amount;
+        if let Some(keeper) = keeper {
+            let keeper_amount = Self::bps_of(earned, config.keeper_bps);
+            if keeper_amount > 0 {
+                platform_amount -= keeper_amount;
+                payouts.add(keeper, keeper_amount)?;
+                Events::keeper_rewarded(env, milestone_index, keeper, keeper_amount);
+            }
+        }
 
-        let token        = Storage::get_token(env)?;
-        let developer    = Storage::get_developer(env)?;
-        let backend      = Storage::get_backend(env)?;  // fee goes to backend/platform
-        let token_client = token::Client::new(env, &token);
+        if retained > 0 {
+            let held = Storage::get_retained_amount(env)?;
+            Storage::set_retained_amount(env, held + retained);
+            Events::retention_held(env, milestone_index, retained);
+        }
 
-        token_client.transfer(
-            &env.current_contract_address(),
-            &developer,
-            &dev_amount,
-        );
-        token_client.transfer(
-            &env.current_contract_address(),
-            &backend,
-            &fee_amount,
-        );
+        payouts.add(&developer, dev_amount)?;
+        payouts.add(&backend, platform_amount)?;
 
         // Update released total
         let released = Storage::get_released_amount(env)?;
@@ -483,27 +2646,198 @@ impl StreamEscrow {
             ..milestone
         });
         Storage::set_milestones(env, &milestones);
+        Registry::untrack(env, milestone_index)?;
 
         Events::funds_released(env, milestone_index, &developer, dev_amount, auto);
 
-        Self::check_completion(env)?;
+        Ok(())
+    }
+
+    /// Penalty owed for a milestone completed after its due_at under
+    /// LatePolicy::Penalty — bps per started day, capped at the full amount.
+    /// Lateness is measured at mark_complete() (completed_at). Emits `late`.
+    fn late_penalty(
+        env: &Env,
+        milestone_index: u32,
+        milestone: &Milestone,
+        policy: &LatePolicy,
+    ) -> Result<i128, Error> {
+        let bps_per_day = match policy {
+            LatePolicy::Penalty(bps) => *bps,
+            _ => return Ok(0),
+        };
+        let (due_at, completed_at) = match (milestone.due_at, milestone.completed_at) {
+            (Some(due_at), Some(completed_at)) if completed_at > due_at => (due_at, completed_at),
+            _ => return Ok(0),
+        };
+
+        let days_late = (completed_at - due_at).div_ceil(86_400);
+        let bps = (days_late as u128 * bps_per_day as u128).min(10_000) as u32;
+        let penalty = Self::bps_of(milestone.amount, bps);
+
+        Events::late_penalty(env, milestone_index, days_late, penalty);
+
+        Ok(penalty)
+    }
+
+    fn push_result(
+        results: &mut Vec<BatchResult>,
+        milestone_index: u32,
+        outcome: Result<(), Error>,
+        atomic: bool,
+    ) -> Result<(), Error> {
+        if let Err(e) = outcome {
+            if atomic {
+                return Err(e);
+            }
+        }
+
+        results.push_back(BatchResult {
+            milestone_index,
+            error: outcome.err().map(|e| e as u32),
+        });
 
         Ok(())
     }
 
+    fn party_address(env: &Env, party: &Party) -> Result<Address, Error> {
+        match party {
+            Party::Client    => Storage::get_client(env),
+            Party::Developer => Storage::get_developer(env),
+        }
+    }
+
+    fn counterparty(party: &Party) -> Party {
+        match party {
+            Party::Client    => Party::Developer,
+            Party::Developer => Party::Client,
+        }
+    }
+
+    /// Return the milestone list as it would look after `order`, or the
+    /// reason it can't apply. Doesn't write anything.
+    fn apply_change_order(env: &Env, order: &ChangeOrder) -> Result<Vec<Milestone>, Error> {
+        let mut milestones = Storage::get_milestones(env)?;
+
+        for milestone_index in order.remove.iter() {
+            let milestone = milestones
+                .get(milestone_index)
+                .ok_or(Error::MilestoneNotFound)?;
+            if milestone.status != MilestoneStatus::Pending {
+                return Err(Error::MilestoneNotPending);
+            }
+            milestones.set(milestone_index, Milestone {
+                status: MilestoneStatus::Removed,
+                bps:    0,
+                amount: 0,
+                ..milestone
+            });
+        }
+
+        for update in order.rebalance.iter() {
+            let milestone = milestones
+                .get(update.milestone_index)
+                .ok_or(Error::MilestoneNotFound)?;
+            if milestone.status != MilestoneStatus::Pending {
+                return Err(Error::MilestoneNotPending);
+            }
+            // Fixed milestones have no bps to rebalance
+            if milestone.bps == 0 || update.bps == 0 {
+                return Err(Error::InvalidMilestoneAmount);
+            }
+            milestones.set(update.milestone_index, Milestone {
+                bps: update.bps,
+                ..milestone
+            });
+        }
+
+        for milestone in order.add.iter() {
+            Self::validate_new_milestone(&milestone)?;
+            Self::validate_share(&milestone)?;
+            milestones.push_back(milestone);
+        }
+
+        if milestones.len() > MAX_MILESTONES {
+            return Err(Error::TooManyMilestones);
+        }
+
+        Self::validate_bps(&milestones)?;
+
+        // Pending bps milestones split whatever isn't released, in flight or fixed
+        let total    = Storage::get_total_amount(env)?;
+        let released = Storage::get_released_amount(env)?;
+        Self::allocate_amounts(&mut milestones, total, released, true)?;
+
+        // Upfront escrows hold the whole pool, so every Pending milestone stays
+        // funded. Just-in-time ones keep funding only where the amount didn't
+        // change — accept_change_order() refunds the rest and the client re-funds.
+        let old = Storage::get_milestones(env)?;
+        let upfront = Storage::get_config(env)?.funding_mode == FundingMode::Upfront;
+        for index in 0..milestones.len() {
+            let m = milestones.get(index).ok_or(Error::MilestoneNotFound)?;
+            if m.status != MilestoneStatus::Pending {
+                continue;
+            }
+            let funded = upfront
+                || old
+                    .get(index)
+                    .map(|o| o.funded && o.amount == m.amount)
+                    .unwrap_or(false);
+            milestones.set(index, Milestone { funded, ..m });
+        }
+
+        Ok(milestones)
+    }
+
     fn check_completion(env: &Env) -> Result<(), Error> {
         let milestones = Storage::get_milestones(env)?;
 
-        // Completed when every milestone is Released or Refunded (no Pending/Disputed left)
-        let all_done = milestones.iter().all(|m| {
-            matches!(m.status, MilestoneStatus::Released | MilestoneStatus::Refunded)
-        });
+        // Done when every milestone is Released, Refunded or Removed (no Pending/Disputed left)
+        let all_done = milestones.iter().all(|m| Self::is_settled(&m.status));
 
-        if all_done {
-            Storage::set_status(env, EscrowStatus::Completed);
-            Events::completed(env);
+        if !all_done {
+            return Ok(());
+        }
+
+        // Retention outlives the milestones — hold it through the warranty
+        let retained = Storage::get_retained_amount(env)?;
+        if retained > 0 {
+            let config  = Storage::get_config(env)?;
+            let ends_at = env
+                .ledger()
+                .timestamp()
+                .checked_add(config.warranty_secs)
+                .ok_or(Error::Overflow)?;
+            Storage::set_warranty_ends_at(env, ends_at);
+            Storage::set_status(env, EscrowStatus::Warranty);
+            Events::warranty_started(env, retained, ends_at);
+        } else {
+            Self::finish(env);
         }
 
         Ok(())
     }
 }
+
+// ─── Keyword matching ─────────────────────────────────────────────────────────
+
+/// ASCII case-insensitive substring check. An empty needle never matches,
+/// so a milestone without a keyword can't be completed by trigger.
+fn contains_ignore_case(haystack: &String, needle: &String) -> bool {
+    let needle_len = needle.len() as usize;
+    if needle_len == 0 || needle_len > haystack.len() as usize {
+        return false;
+    }
+
+    let haystack = lowercase_bytes(haystack);
+    let needle   = lowercase_bytes(needle);
+
+    haystack.windows(needle_len).any(|window| window == needle.as_slice())
+}
+
+fn lowercase_bytes(s: &String) -> alloc::vec::Vec<u8> {
+    let mut buf = alloc::vec![0u8; s.len() as usize];
+    s.copy_into_slice(&mut buf);
+    buf.make_ascii_lowercase();
+    buf
+}
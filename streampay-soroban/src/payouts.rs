@@ -0,0 +1,52 @@
+// payouts.rs — Coalesced token transfers out of the escrow.
+//
+// Every code path that pays someone (developer, platform fee, client refund)
+// records the amount here first and sends the tokens in one go at the end.
+// A batch that releases five milestones therefore costs one transfer per
+// recipient instead of two transfers per milestone.
+
+use soroban_sdk::{token, Address, Env, Map};
+
+use crate::errors::Error;
+use crate::storage::Storage;
+
+pub struct Payouts {
+    amounts: Map<Address, i128>,
+}
+
+impl Payouts {
+
+    pub fn new(env: &Env) -> Self {
+        Payouts { amounts: Map::new(env) }
+    }
+
+    /// Queue `amount` for `recipient`. Zero amounts are dropped.
+    pub fn add(&mut self, recipient: &Address, amount: i128) -> Result<(), Error> {
+        if amount <= 0 {
+            return Ok(());
+        }
+
+        let queued = self.amounts.get(recipient.clone()).unwrap_or(0);
+        let total  = queued.checked_add(amount).ok_or(Error::Overflow)?;
+        self.amounts.set(recipient.clone(), total);
+
+        Ok(())
+    }
+
+    /// Send one transfer per recipient from the contract's balance.
+    pub fn flush(self, env: &Env) -> Result<(), Error> {
+        if self.amounts.is_empty() {
+            return Ok(());
+        }
+
+        let token        = Storage::get_token(env)?;
+        let token_client = token::Client::new(env, &token);
+        let contract     = env.current_contract_address();
+
+        for (recipient, amount) in self.amounts.iter() {
+            token_client.transfer(&contract, &recipient, &amount);
+        }
+
+        Ok(())
+    }
+}
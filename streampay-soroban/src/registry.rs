@@ -0,0 +1,57 @@
+// registry.rs — Optional push of deadlines to a shared KeeperRegistry.
+//
+// If EscrowConfig.registry is set, every milestone that starts a countdown
+// is registered there with its due timestamp, and cleared again when it
+// leaves review. Keepers query the registry instead of polling each escrow.
+//
+// Only the registry's interface is declared here — the registry contract
+// itself lives in registry/ and is deployed once per network.
+//
+// Calls go through try_register / try_clear and failures are ignored, so a
+// registry that errors, panics or isn't deployed can't block a release,
+// dispute or refund. That is not a sandbox: running out of budget inside
+// the registry still aborts the whole call, so config.registry must only
+// ever name the trusted KeeperRegistry — never an arbitrary contract.
+
+use soroban_sdk::{contractclient, Address, Env};
+
+use crate::errors::Error;
+use crate::storage::Storage;
+
+// Only the generated client is used; the trait itself is never implemented here.
+#[allow(dead_code)]
+#[contractclient(name = "KeeperRegistryClient")]
+pub trait KeeperRegistryInterface {
+    fn register(env: Env, escrow: Address, milestone_index: u32, due_at: u64);
+    fn clear(env: Env, escrow: Address, milestone_index: u32);
+}
+
+pub struct Registry;
+
+impl Registry {
+
+    /// Register (or move) a milestone's deadline. No-op without a registry;
+    /// a failing registry is ignored.
+    pub fn track(env: &Env, milestone_index: u32, due_at: u64) -> Result<(), Error> {
+        if let Some(registry) = Storage::get_config(env)?.registry {
+            let _ = KeeperRegistryClient::new(env, &registry).try_register(
+                &env.current_contract_address(),
+                &milestone_index,
+                &due_at,
+            );
+        }
+        Ok(())
+    }
+
+    /// Drop a milestone's deadline. No-op without a registry; a failing
+    /// registry is ignored.
+    pub fn untrack(env: &Env, milestone_index: u32) -> Result<(), Error> {
+        if let Some(registry) = Storage::get_config(env)?.registry {
+            let _ = KeeperRegistryClient::new(env, &registry).try_clear(
+                &env.current_contract_address(),
+                &milestone_index,
+            );
+        }
+        Ok(())
+    }
+}
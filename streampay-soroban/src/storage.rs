@@ -14,10 +14,13 @@
 //
 // We use Persistent for escrow data (can't let it expire mid-deal).
 
-use soroban_sdk::{contracttype, Address, Env, Vec};
+use soroban_sdk::{contracttype, Address, Env, Map, Vec};
 
 use crate::errors::Error;
-use crate::types::{EscrowStatus, Milestone};
+use crate::types::{
+    EscrowConfig, EscrowMetadata, EscrowStatus, Milestone, PayoutChange, PendingChangeOrder,
+    PendingReplacement, PendingTermination, TermsProposal, WarrantyClaim,
+};
 
 // ─── Storage keys ─────────────────────────────────────────────────────────────
 
@@ -25,14 +28,34 @@ use crate::types::{EscrowStatus, Milestone};
 pub enum DataKey {
     Initialized,
     Client,
+    PendingClient,
     Developer,
+    PayoutAddress,
+    PendingPayout,
+    MilestonePayout(u32),
     Backend,
+    BackendSigners,
     Token,
     TotalAmount,
     ReleasedAmount,
+    AdvanceAmount,
+    RetainedAmount,
+    WarrantyEndsAt,
+    WarrantyClaim,
+    CancelAt,
+    DeveloperWithdrawn,
     Status,
     DisputeWindow,
+    Config,
+    Metadata,
     Milestones,
+    TermsVersion,
+    ChangeOrder,
+    Proposal,
+    Termination,
+    Replacement,
+    FeeAdmin,
+    AdminRemovalAt,
 }
 
 // ─── Storage helpers ──────────────────────────────────────────────────────────
@@ -86,6 +109,43 @@ impl Storage {
         env.storage().persistent().set(&DataKey::Backend, backend);
     }
 
+    /// Signer admin the platform had when the escrow went Active (or handed
+    /// over to since) — never one the parties installed themselves.
+    pub fn get_fee_admin(env: &Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::FeeAdmin)
+    }
+
+    pub fn set_fee_admin(env: &Env, admin: &Option<Address>) {
+        match admin {
+            Some(admin) => env.storage().persistent().set(&DataKey::FeeAdmin, admin),
+            None        => env.storage().persistent().remove(&DataKey::FeeAdmin),
+        }
+    }
+
+    pub fn get_admin_removal_at(env: &Env) -> Option<u64> {
+        env.storage().persistent().get(&DataKey::AdminRemovalAt)
+    }
+
+    pub fn set_admin_removal_at(env: &Env, at: u64) {
+        env.storage().persistent().set(&DataKey::AdminRemovalAt, &at);
+    }
+
+    pub fn remove_admin_removal_at(env: &Env) {
+        env.storage().persistent().remove(&DataKey::AdminRemovalAt);
+    }
+
+    /// Backend signer → expiry timestamp (0 = never expires).
+    pub fn get_backend_signers(env: &Env) -> Result<Map<Address, u64>, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BackendSigners)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn set_backend_signers(env: &Env, signers: &Map<Address, u64>) {
+        env.storage().persistent().set(&DataKey::BackendSigners, signers);
+    }
+
     pub fn get_token(env: &Env) -> Result<Address, Error> {
         env.storage()
             .persistent()
@@ -121,6 +181,148 @@ impl Storage {
         env.storage().persistent().set(&DataKey::ReleasedAmount, &amount);
     }
 
+    pub fn get_advance_amount(env: &Env) -> Result<i128, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::AdvanceAmount)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn set_advance_amount(env: &Env, amount: i128) {
+        env.storage().persistent().set(&DataKey::AdvanceAmount, &amount);
+    }
+
+    // ─── Retention / warranty ─────────────────────────────────────────────────
+
+    pub fn get_retained_amount(env: &Env) -> Result<i128, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RetainedAmount)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn set_retained_amount(env: &Env, amount: i128) {
+        env.storage().persistent().set(&DataKey::RetainedAmount, &amount);
+    }
+
+    pub fn get_warranty_ends_at(env: &Env) -> Option<u64> {
+        env.storage().persistent().get(&DataKey::WarrantyEndsAt)
+    }
+
+    pub fn set_warranty_ends_at(env: &Env, ends_at: u64) {
+        env.storage().persistent().set(&DataKey::WarrantyEndsAt, &ends_at);
+    }
+
+    pub fn get_warranty_claim(env: &Env) -> Option<WarrantyClaim> {
+        env.storage().persistent().get(&DataKey::WarrantyClaim)
+    }
+
+    pub fn set_warranty_claim(env: &Env, claim: &WarrantyClaim) {
+        env.storage().persistent().set(&DataKey::WarrantyClaim, claim);
+    }
+
+    pub fn remove_warranty_claim(env: &Env) {
+        env.storage().persistent().remove(&DataKey::WarrantyClaim);
+    }
+
+    // ─── Client transfer ──────────────────────────────────────────────────────
+
+    pub fn get_pending_client(env: &Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::PendingClient)
+    }
+
+    pub fn set_pending_client(env: &Env, client: &Address) {
+        env.storage().persistent().set(&DataKey::PendingClient, client);
+    }
+
+    pub fn remove_pending_client(env: &Env) {
+        env.storage().persistent().remove(&DataKey::PendingClient);
+    }
+
+    // ─── Payout address ───────────────────────────────────────────────────────
+
+    pub fn get_payout_address(env: &Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::PayoutAddress)
+    }
+
+    pub fn set_payout_address(env: &Env, address: &Address) {
+        env.storage().persistent().set(&DataKey::PayoutAddress, address);
+    }
+
+    pub fn remove_payout_address(env: &Env) {
+        env.storage().persistent().remove(&DataKey::PayoutAddress);
+    }
+
+    pub fn get_pending_payout(env: &Env) -> Option<PayoutChange> {
+        env.storage().persistent().get(&DataKey::PendingPayout)
+    }
+
+    pub fn set_pending_payout(env: &Env, change: &PayoutChange) {
+        env.storage().persistent().set(&DataKey::PendingPayout, change);
+    }
+
+    pub fn remove_pending_payout(env: &Env) {
+        env.storage().persistent().remove(&DataKey::PendingPayout);
+    }
+
+    pub fn get_milestone_payout(env: &Env, index: u32) -> Option<PayoutChange> {
+        env.storage().persistent().get(&DataKey::MilestonePayout(index))
+    }
+
+    pub fn set_milestone_payout(env: &Env, index: u32, change: &PayoutChange) {
+        env.storage().persistent().set(&DataKey::MilestonePayout(index), change);
+    }
+
+    pub fn remove_milestone_payout(env: &Env, index: u32) {
+        env.storage().persistent().remove(&DataKey::MilestonePayout(index));
+    }
+
+    // ─── Developer replacement ────────────────────────────────────────────────
+
+    pub fn get_replacement(env: &Env) -> Option<PendingReplacement> {
+        env.storage().persistent().get(&DataKey::Replacement)
+    }
+
+    pub fn set_replacement(env: &Env, replacement: &PendingReplacement) {
+        env.storage().persistent().set(&DataKey::Replacement, replacement);
+    }
+
+    pub fn remove_replacement(env: &Env) {
+        env.storage().persistent().remove(&DataKey::Replacement);
+    }
+
+    // ─── Termination ──────────────────────────────────────────────────────────
+
+    pub fn get_termination(env: &Env) -> Option<PendingTermination> {
+        env.storage().persistent().get(&DataKey::Termination)
+    }
+
+    pub fn set_termination(env: &Env, termination: &PendingTermination) {
+        env.storage().persistent().set(&DataKey::Termination, termination);
+    }
+
+    pub fn remove_termination(env: &Env) {
+        env.storage().persistent().remove(&DataKey::Termination);
+    }
+
+    // ─── Wind-down ────────────────────────────────────────────────────────────
+
+    pub fn get_cancel_at(env: &Env) -> Option<u64> {
+        env.storage().persistent().get(&DataKey::CancelAt)
+    }
+
+    pub fn set_cancel_at(env: &Env, cancel_at: u64) {
+        env.storage().persistent().set(&DataKey::CancelAt, &cancel_at);
+    }
+
+    pub fn is_developer_withdrawn(env: &Env) -> bool {
+        env.storage().persistent().has(&DataKey::DeveloperWithdrawn)
+    }
+
+    pub fn set_developer_withdrawn(env: &Env) {
+        env.storage().persistent().set(&DataKey::DeveloperWithdrawn, &true);
+    }
+
     // ─── Status ───────────────────────────────────────────────────────────────
 
     pub fn get_status(env: &Env) -> Result<EscrowStatus, Error> {
@@ -147,6 +349,30 @@ impl Storage {
         env.storage().persistent().set(&DataKey::DisputeWindow, &secs);
     }
 
+    // ─── Config ───────────────────────────────────────────────────────────────
+
+    pub fn get_metadata(env: &Env) -> Result<EscrowMetadata, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Metadata)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn set_metadata(env: &Env, metadata: &EscrowMetadata) {
+        env.storage().persistent().set(&DataKey::Metadata, metadata);
+    }
+
+    pub fn get_config(env: &Env) -> Result<EscrowConfig, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Config)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn set_config(env: &Env, config: &EscrowConfig) {
+        env.storage().persistent().set(&DataKey::Config, config);
+    }
+
     // ─── Milestones ───────────────────────────────────────────────────────────
 
     pub fn get_milestones(env: &Env) -> Result<Vec<Milestone>, Error> {
@@ -159,4 +385,43 @@ impl Storage {
     pub fn set_milestones(env: &Env, milestones: &Vec<Milestone>) {
         env.storage().persistent().set(&DataKey::Milestones, milestones);
     }
+
+    // ─── Terms proposal ───────────────────────────────────────────────────────
+
+    pub fn get_proposal(env: &Env) -> Option<TermsProposal> {
+        env.storage().persistent().get(&DataKey::Proposal)
+    }
+
+    pub fn set_proposal(env: &Env, proposal: &TermsProposal) {
+        env.storage().persistent().set(&DataKey::Proposal, proposal);
+    }
+
+    pub fn remove_proposal(env: &Env) {
+        env.storage().persistent().remove(&DataKey::Proposal);
+    }
+
+    // ─── Change orders ────────────────────────────────────────────────────────
+
+    pub fn get_terms_version(env: &Env) -> Result<u32, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::TermsVersion)
+            .ok_or(Error::NotInitialized)
+    }
+
+    pub fn set_terms_version(env: &Env, version: u32) {
+        env.storage().persistent().set(&DataKey::TermsVersion, &version);
+    }
+
+    pub fn get_change_order(env: &Env) -> Option<PendingChangeOrder> {
+        env.storage().persistent().get(&DataKey::ChangeOrder)
+    }
+
+    pub fn set_change_order(env: &Env, order: &PendingChangeOrder) {
+        env.storage().persistent().set(&DataKey::ChangeOrder, order);
+    }
+
+    pub fn remove_change_order(env: &Env) {
+        env.storage().persistent().remove(&DataKey::ChangeOrder);
+    }
 }
@@ -1,12 +1,19 @@
 #![cfg(test)]
+// Amounts are written as <whole USDC>_<7 decimal stroops>.
+#![allow(clippy::inconsistent_digit_grouping)]
 
 use soroban_sdk::{
-    testutils::{Address as _, Ledger, LedgerInfo},
-    token, Address, Env, String, Vec,
+    testutils::{Address as _, Events as _, Ledger, LedgerInfo},
+    token, Address, Bytes, BytesN, Env, IntoVal, String, Symbol, Vec,
 };
 
+use keeper_registry::{KeeperRegistry, KeeperRegistryClient, MAX_ESCROW_DEADLINES};
+
 use crate::{
-    types::{EscrowStatus, Milestone, MilestoneStatus},
+    types::{
+        ApprovalTimeout, BatchResult, BpsUpdate, ChangeOrder, EscrowConfig, EscrowMetadata, EscrowStatus,
+        FundingMode, LatePolicy, Milestone, MilestoneStatus, Party,
+    },
     StreamEscrow, StreamEscrowClient,
 };
 
@@ -38,7 +45,7 @@ fn setup() -> TestEnv {
 
     token_client.mint(&client, &1_000_000_0000000i128);
 
-    let contract_id = env.register_contract(None, StreamEscrow);
+    let contract_id = env.register(StreamEscrow, ());
 
     TestEnv { env, token, client, developer, backend, contract_id }
 }
@@ -47,32 +54,31 @@ fn client<'a>(t: &'a TestEnv) -> StreamEscrowClient<'a> {
     StreamEscrowClient::new(&t.env, &t.contract_id)
 }
 
+fn milestone(env: &Env, title: &str, trigger_keyword: &str, bps: u32) -> Milestone {
+    Milestone {
+        title:           String::from_str(env, title),
+        trigger_keyword: String::from_str(env, trigger_keyword),
+        bps,
+        amount:          0,
+        status:          MilestoneStatus::Pending,
+        pr_url:          None,
+        completed_at:    None,
+        change_requests: 0,
+        funded:          false,
+        due_at:          None,
+        payee:           None,
+    }
+}
+
+fn fixed_milestone(env: &Env, title: &str, trigger_keyword: &str, amount: i128) -> Milestone {
+    Milestone { bps: 0, amount, ..milestone(env, title, trigger_keyword, 0) }
+}
+
 fn make_milestones(env: &Env) -> Vec<Milestone> {
     let mut ms = Vec::new(env);
-    ms.push_back(Milestone {
-        title:        String::from_str(env, "Design"),
-        trigger_keyword:   String::from_str(env, "feat/design"),
-        bps:          3000,
-        status:       MilestoneStatus::Pending,
-        pr_url:       None,
-        completed_at: None,
-    });
-    ms.push_back(Milestone {
-        title:        String::from_str(env, "Backend"),
-        trigger_keyword:   String::from_str(env, "feat/backend"),
-        bps:          4000,
-        status:       MilestoneStatus::Pending,
-        pr_url:       None,
-        completed_at: None,
-    });
-    ms.push_back(Milestone {
-        title:        String::from_str(env, "Delivery"),
-        trigger_keyword:   String::from_str(env, "feat/delivery"),
-        bps:          3000,
-        status:       MilestoneStatus::Pending,
-        pr_url:       None,
-        completed_at: None,
-    });
+    ms.push_back(milestone(env, "Design",   "feat/design",   3000));
+    ms.push_back(milestone(env, "Backend",  "feat/backend",  4000));
+    ms.push_back(milestone(env, "Delivery", "feat/delivery", 3000));
     ms
 }
 
@@ -97,13 +103,59 @@ fn token_balance(env: &Env, token: &Address, addr: &Address) -> i128 {
     token::Client::new(env, token).balance(addr)
 }
 
+fn default_config() -> EscrowConfig {
+    EscrowConfig {
+        keeper_bps:             0,
+        registry:               None,
+        signer_admin:           None,
+        approval_deadline_secs: 0,
+        approval_timeout:       ApprovalTimeout::Escalate,
+        advance_bps:            0,
+        advance_amount:         0,
+        retention_bps:          0,
+        warranty_secs:          0,
+        late_policy:            LatePolicy::Disabled,
+        expires_at:             0,
+        dispute_default:        Party::Client,
+        kill_fee_bps:           0,
+        cancel_notice_secs:     0,
+        payout_delay_secs:      0,
+        replacement_timeout_secs: 0,
+        proposal_ttl_secs:      0,
+        funding_mode:           FundingMode::Upfront,
+        funded_ahead:           0,
+    }
+}
+
+fn metadata(env: &Env) -> EscrowMetadata {
+    EscrowMetadata {
+        external_id:    Bytes::from_slice(env, b"esc_8f3a2c"),
+        terms_doc_hash: BytesN::from_array(env, &[1u8; 32]),
+        title:          String::from_str(env, "Acme API rebuild"),
+        repo:           String::from_str(env, "acme/api"),
+    }
+}
+
 fn do_initialize(t: &TestEnv, dispute_window: u64) {
+    do_initialize_with(t, dispute_window, default_config());
+}
+
+fn do_initialize_with(t: &TestEnv, dispute_window: u64, config: EscrowConfig) {
     let c  = client(t);
     let ms = make_milestones(&t.env);
     c.initialize(
         &t.client, &t.developer, &t.backend,
-        &t.token, &1_000_0000000i128, &ms, &dispute_window,
+        &t.token, &1_000_0000000i128, &ms, &dispute_window, &config, &metadata(&t.env),
     );
+    accept_and_fund(t);
+}
+
+/// Developer accepts the proposed terms and the client funds them.
+fn accept_and_fund(t: &TestEnv) {
+    let c    = client(t);
+    let hash = c.get_terms_hash();
+    c.accept_terms(&hash);
+    c.fund(&hash);
 }
 
 // ─── Initialize ───────────────────────────────────────────────────────────────
@@ -131,7 +183,7 @@ fn test_initialize_wrong_bps() {
     m.bps = 9999;
     ms.set(0, m);
     c.initialize(&t.client, &t.developer, &t.backend,
-                 &t.token, &1_000_0000000i128, &ms, &259200u64);
+                 &t.token, &1_000_0000000i128, &ms, &259200u64, &default_config(), &metadata(&t.env));
 }
 
 #[test]
@@ -142,6 +194,252 @@ fn test_initialize_twice_fails() {
     do_initialize(&t, 259200);
 }
 
+#[test]
+fn test_initialize_fixed_and_bps_amounts() {
+    let t = setup();
+    let c = client(&t);
+
+    // 1000 total: Design is a flat 125, the rest split 1/3 : 2/3
+    let mut ms = Vec::new(&t.env);
+    ms.push_back(fixed_milestone(&t.env, "Design", "feat/design", 125_0000000));
+    ms.push_back(milestone(&t.env, "Backend",  "feat/backend",  3333));
+    ms.push_back(milestone(&t.env, "Delivery", "feat/delivery", 6667));
+    c.initialize(&t.client, &t.developer, &t.backend,
+                 &t.token, &1_000_0000000i128, &ms, &259200u64, &default_config(), &metadata(&t.env));
+    accept_and_fund(&t);
+
+    let ms = c.get_milestones();
+    assert_eq!(ms.get(0).unwrap().amount, 125_0000000);
+    assert_eq!(ms.get(1).unwrap().amount, 291_6375000);
+    assert_eq!(ms.get(2).unwrap().amount, 583_3625000);
+
+    for i in 0u32..3 {
+        c.mark_complete(&t.backend, &i, &pr_url(&t.env));
+        c.approve(&i);
+    }
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+}
+
+#[test]
+fn test_initialize_all_fixed_amounts() {
+    let t = setup();
+    let c = client(&t);
+
+    let mut ms = Vec::new(&t.env);
+    ms.push_back(fixed_milestone(&t.env, "Design",  "feat/design",  400_0000000));
+    ms.push_back(fixed_milestone(&t.env, "Backend", "feat/backend", 600_0000000));
+    c.initialize(&t.client, &t.developer, &t.backend,
+                 &t.token, &1_000_0000000i128, &ms, &259200u64, &default_config(), &metadata(&t.env));
+    accept_and_fund(&t);
+
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.approve(&1);
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 600_0000000i128 * 99 / 100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")]
+fn test_initialize_fixed_amounts_must_match_total() {
+    let t = setup();
+    let c = client(&t);
+
+    let mut ms = Vec::new(&t.env);
+    ms.push_back(fixed_milestone(&t.env, "Design",  "feat/design",  400_0000000));
+    ms.push_back(fixed_milestone(&t.env, "Backend", "feat/backend", 500_0000000));
+    c.initialize(&t.client, &t.developer, &t.backend,
+                 &t.token, &1_000_0000000i128, &ms, &259200u64, &default_config(), &metadata(&t.env));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")]
+fn test_initialize_bps_and_amount_together_fails() {
+    let t = setup();
+    let c = client(&t);
+
+    let mut ms = make_milestones(&t.env);
+    let mut m = ms.get(0).unwrap();
+    m.amount = 300_0000000;
+    ms.set(0, m);
+    c.initialize(&t.client, &t.developer, &t.backend,
+                 &t.token, &1_000_0000000i128, &ms, &259200u64, &default_config(), &metadata(&t.env));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #38)")]
+fn test_initialize_with_payee_fails() {
+    let t = setup();
+    let c = client(&t);
+
+    // Payees are the contract's to set — terms can't redirect a milestone
+    let mut ms = make_milestones(&t.env);
+    let m = ms.get(0).unwrap();
+    ms.set(0, Milestone { payee: Some(Address::generate(&t.env)), ..m });
+    c.initialize(&t.client, &t.developer, &t.backend,
+                 &t.token, &1_000_0000000i128, &ms, &259200u64, &default_config(), &metadata(&t.env));
+}
+
+// ─── Metadata ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_metadata_view_and_event_topic() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    assert_eq!(c.get_metadata(), metadata(&t.env));
+
+    // Every event carries the external id as its second topic
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    let (_, topics, _) = t.env.events().all().last().unwrap();
+    let external_id: Bytes = topics.get(1).unwrap().into_val(&t.env);
+    assert_eq!(external_id, Bytes::from_slice(&t.env, b"esc_8f3a2c"));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #17)")]
+fn test_empty_external_id_rejected() {
+    let t = setup();
+    let meta = EscrowMetadata { external_id: Bytes::new(&t.env), ..metadata(&t.env) };
+    client(&t).initialize(
+        &t.client, &t.developer, &t.backend,
+        &t.token, &1_000_0000000i128, &make_milestones(&t.env), &259200u64, &default_config(),
+        &meta,
+    );
+}
+
+// ─── Draft / terms acceptance ─────────────────────────────────────────────────
+
+fn do_propose(t: &TestEnv, config: EscrowConfig) {
+    client(t).initialize(
+        &t.client, &t.developer, &t.backend,
+        &t.token, &1_000_0000000i128, &make_milestones(&t.env), &259200u64, &config,
+        &metadata(&t.env),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #24)")]
+fn test_draft_locks_nothing() {
+    let t = setup();
+    do_propose(&t, default_config());
+    let c = client(&t);
+
+    assert_eq!(c.get_status(), EscrowStatus::Draft);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #102)")]
+fn test_fund_before_accept_fails() {
+    let t = setup();
+    do_propose(&t, default_config());
+    let c = client(&t);
+    c.fund(&c.get_terms_hash());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #100)")]
+fn test_accept_wrong_hash_fails() {
+    let t = setup();
+    do_propose(&t, default_config());
+    client(&t).accept_terms(&BytesN::from_array(&t.env, &[7u8; 32]));
+}
+
+#[test]
+fn test_client_funds_counter_proposal() {
+    let t = setup();
+    do_propose(&t, default_config());
+    let c = client(&t);
+    let original = c.get_terms_hash();
+
+    c.counter_propose(&1_200_0000000i128, &make_milestones(&t.env), &259200u64, &default_config());
+    let counter = c.get_terms_hash();
+    assert_ne!(original, counter);
+    assert_eq!(c.get_proposal().unwrap().proposer, Party::Developer);
+
+    c.fund(&counter);
+    assert_eq!(c.get_status(), EscrowStatus::Active);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 1_200_0000000);
+    assert!(c.get_proposal().is_none());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #101)")]
+fn test_proposal_expires() {
+    let t = setup();
+    do_propose(&t, EscrowConfig { proposal_ttl_secs: 86400, ..default_config() });
+    let c = client(&t);
+
+    advance_time(&t.env, 86400);
+    c.accept_terms(&c.get_terms_hash());
+}
+
+#[test]
+fn test_withdraw_draft() {
+    let t = setup();
+    do_propose(&t, default_config());
+    let c = client(&t);
+
+    c.withdraw_draft();
+    assert_eq!(c.get_status(), EscrowStatus::Cancelled);
+}
+
+// ─── Advance ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_advance_paid_on_funding() {
+    let t = setup();
+    do_initialize_with(&t, 0, EscrowConfig { advance_bps: 1000, ..default_config() });
+    let c = client(&t);
+
+    // 100 USDC advance less 1% fee
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 99_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.backend), 1_0000000);
+    assert_eq!(c.get_advance(), 100_0000000);
+    assert_eq!(c.get_balance(), (1_000_0000000, 100_0000000, 900_0000000));
+
+    // Milestones split the remaining 900
+    assert_eq!(c.get_milestone_amount(&0), 270_0000000);
+    assert_eq!(c.get_milestone_amount(&1), 360_0000000);
+}
+
+#[test]
+fn test_cancel_after_advance_refunds_remainder() {
+    let t = setup();
+    do_initialize_with(&t, 0, EscrowConfig { advance_amount: 50_0000000, ..default_config() });
+    let c = client(&t);
+
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.cancel();
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+
+    assert_eq!(client_after - client_before, 950_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+}
+
+#[test]
+fn test_advance_with_jit_funding() {
+    let t = setup();
+    let config = EscrowConfig {
+        advance_amount: 50_0000000,
+        funding_mode:   FundingMode::JustInTime,
+        ..default_config()
+    };
+    do_initialize_with(&t, 0, config);
+
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 49_5000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn test_advance_bps_and_amount_rejected() {
+    let t = setup();
+    let config = EscrowConfig { advance_bps: 500, advance_amount: 1, ..default_config() };
+    do_initialize_with(&t, 0, config);
+}
+
 // ─── Happy path ───────────────────────────────────────────────────────────────
 
 #[test]
@@ -153,7 +451,7 @@ fn test_full_happy_path_client_approves() {
     let dev_before = token_balance(&t.env, &t.token, &t.developer);
 
     for i in 0u32..3 {
-        c.mark_complete(&i, &pr_url(&t.env));
+        c.mark_complete(&t.backend, &i, &pr_url(&t.env));
         assert_eq!(c.get_milestone(&i).status, MilestoneStatus::PendingRelease);
         c.approve(&i);
         assert_eq!(c.get_milestone(&i).status, MilestoneStatus::Released);
@@ -166,6 +464,80 @@ fn test_full_happy_path_client_approves() {
     assert_eq!(dev_after - dev_before, expected);
 }
 
+// ─── Complete by trigger ──────────────────────────────────────────────────────
+
+fn labels(env: &Env, items: &[&str]) -> Vec<String> {
+    let mut v = Vec::new(env);
+    for item in items {
+        v.push_back(String::from_str(env, item));
+    }
+    v
+}
+
+#[test]
+fn test_complete_by_trigger_title_case_insensitive() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    let index = c.complete_by_trigger(
+        &t.backend,
+        &String::from_str(&t.env, "FEAT/Backend: add invoices API"),
+        &labels(&t.env, &[]),
+        &pr_url(&t.env),
+    );
+
+    assert_eq!(index, 1);
+    let m = c.get_milestone(&1);
+    assert_eq!(m.status, MilestoneStatus::PendingRelease);
+    assert_eq!(m.pr_url, Some(pr_url(&t.env)));
+}
+
+#[test]
+fn test_complete_by_trigger_label_skips_completed() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+
+    // Design is already PendingRelease, so only Delivery is a candidate
+    let index = c.complete_by_trigger(
+        &t.backend,
+        &String::from_str(&t.env, "Final polish"),
+        &labels(&t.env, &["feat/design", "Feat/Delivery"]),
+        &pr_url(&t.env),
+    );
+    assert_eq!(index, 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #35)")]
+fn test_complete_by_trigger_no_match_fails() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.complete_by_trigger(
+        &t.backend,
+        &String::from_str(&t.env, "chore: bump deps"),
+        &labels(&t.env, &["dependencies"]),
+        &pr_url(&t.env),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #36)")]
+fn test_complete_by_trigger_ambiguous_fails() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.complete_by_trigger(
+        &t.backend,
+        &String::from_str(&t.env, "feat/design + feat/backend"),
+        &labels(&t.env, &[]),
+        &pr_url(&t.env),
+    );
+}
+
 // ─── Auto-release ─────────────────────────────────────────────────────────────
 
 #[test]
@@ -174,8 +546,8 @@ fn test_auto_release_too_early_fails() {
     let t = setup();
     do_initialize(&t, 259200);
     let c = client(&t);
-    c.mark_complete(&0, &pr_url(&t.env));
-    c.auto_release(&0);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.auto_release(&0, &None);
 }
 
 #[test]
@@ -183,11 +555,11 @@ fn test_auto_release_after_window() {
     let t = setup();
     do_initialize(&t, 259200);
     let c = client(&t);
-    c.mark_complete(&0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
     advance_time(&t.env, 259201);
 
     let dev_before = token_balance(&t.env, &t.token, &t.developer);
-    c.auto_release(&0);
+    c.auto_release(&0, &None);
     let dev_after  = token_balance(&t.env, &t.token, &t.developer);
 
     let expected = 300_0000000i128 * 99 / 100;
@@ -200,102 +572,1629 @@ fn test_auto_release_no_window_fails() {
     let t = setup();
     do_initialize(&t, 0);
     let c = client(&t);
-    c.mark_complete(&0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
     advance_time(&t.env, 999999);
-    c.auto_release(&0);
+    c.auto_release(&0, &None);
 }
 
-// ─── Dispute ──────────────────────────────────────────────────────────────────
-
 #[test]
-fn test_dispute_within_window() {
+fn test_auto_release_pays_keeper_from_fee() {
     let t = setup();
-    do_initialize(&t, 259200);
+    do_initialize_with(&t, 259200, EscrowConfig { keeper_bps: 50, ..default_config() });
     let c = client(&t);
-    c.mark_complete(&0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    advance_time(&t.env, 259201);
 
-    c.dispute(&0, &String::from_str(&t.env, "Does not match spec"));
-    assert_eq!(c.get_milestone(&0).status, MilestoneStatus::Disputed);
+    let keeper = Address::generate(&t.env);
+    c.auto_release(&0, &Some(keeper.clone()));
+
+    // 300 USDC release: 1% fee = 3 USDC, half of it to the keeper
+    assert_eq!(token_balance(&t.env, &t.token, &keeper), 1_5000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.backend), 1_5000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 300_0000000i128 * 99 / 100);
 }
 
 #[test]
-#[should_panic(expected = "Error(Contract, #42)")]
-fn test_dispute_after_window_fails() {
+fn test_auto_release_without_keeper_keeps_full_fee() {
     let t = setup();
-    do_initialize(&t, 259200);
+    do_initialize_with(&t, 259200, EscrowConfig { keeper_bps: 50, ..default_config() });
     let c = client(&t);
-    c.mark_complete(&0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
     advance_time(&t.env, 259201);
-    c.dispute(&0, &String::from_str(&t.env, "Too late"));
+
+    c.auto_release(&0, &None);
+    assert_eq!(token_balance(&t.env, &t.token, &t.backend), 3_0000000);
 }
 
 #[test]
-fn test_dispute_resolved_dev_wins() {
+#[should_panic(expected = "Error(Contract, #14)")]
+fn test_initialize_keeper_bps_above_cap_fails() {
+    let t = setup();
+    do_initialize_with(&t, 259200, EscrowConfig { keeper_bps: 51, ..default_config() });
+}
+
+// ─── Retract completion ───────────────────────────────────────────────────────
+
+#[test]
+fn test_retract_completion_with_developer() {
     let t = setup();
     do_initialize(&t, 259200);
     let c = client(&t);
-    c.mark_complete(&0, &pr_url(&t.env));
-    c.dispute(&0, &String::from_str(&t.env, "Dispute"));
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
 
-    let dev_before = token_balance(&t.env, &t.token, &t.developer);
-    c.resolve_dispute(&0, &true);
-    let dev_after  = token_balance(&t.env, &t.token, &t.developer);
-    assert!(dev_after > dev_before);
+    c.retract_completion(&t.backend, &1, &t.developer);
+
+    let m = c.get_milestone(&1);
+    assert_eq!(m.status, MilestoneStatus::Pending);
+    assert_eq!(m.pr_url, None);
+    assert_eq!(m.completed_at, None);
+
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    assert_eq!(c.get_milestone(&1).status, MilestoneStatus::PendingRelease);
 }
 
 #[test]
-fn test_dispute_resolved_client_wins() {
+#[should_panic(expected = "Error(Contract, #60)")]
+fn test_retract_completion_backend_alone_fails() {
     let t = setup();
     do_initialize(&t, 259200);
     let c = client(&t);
-    c.mark_complete(&0, &pr_url(&t.env));
-    c.dispute(&0, &String::from_str(&t.env, "Not delivered"));
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.retract_completion(&t.backend, &1, &t.backend);
+}
 
-    let client_before = token_balance(&t.env, &t.token, &t.client);
-    c.resolve_dispute(&0, &false);
-    let client_after  = token_balance(&t.env, &t.token, &t.client);
-    assert_eq!(client_after - client_before, 300_0000000i128);
+#[test]
+#[should_panic(expected = "Error(Contract, #32)")]
+fn test_retract_released_milestone_fails() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.approve(&1);
+    c.retract_completion(&t.backend, &1, &t.client);
 }
 
-// ─── Cancel ───────────────────────────────────────────────────────────────────
+// ─── Request changes ──────────────────────────────────────────────────────────
+
+fn note(env: &Env) -> BytesN<32> {
+    BytesN::from_array(env, &[7u8; 32])
+}
 
 #[test]
-fn test_cancel_full_refund() {
+fn test_request_changes_restarts_window() {
     let t = setup();
     do_initialize(&t, 259200);
     let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
 
-    let before = token_balance(&t.env, &t.token, &t.client);
-    c.cancel();
-    let after  = token_balance(&t.env, &t.token, &t.client);
+    c.request_changes(&0, &note(&t.env));
+    let m = c.get_milestone(&0);
+    assert_eq!(m.status, MilestoneStatus::Pending);
+    assert_eq!(m.completed_at, None);
+    assert_eq!(m.change_requests, 1);
 
-    assert_eq!(after - before, 1_000_0000000i128);
-    assert_eq!(c.get_status(), EscrowStatus::Cancelled);
+    advance_time(&t.env, 1000);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    assert_eq!(c.time_until_auto_release(&0), 259200);
 }
 
 #[test]
-fn test_cancel_partial_after_one_release() {
+#[should_panic(expected = "Error(Contract, #45)")]
+fn test_request_changes_after_window_fails() {
     let t = setup();
     do_initialize(&t, 259200);
     let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    advance_time(&t.env, 259200);
+    c.request_changes(&0, &note(&t.env));
+}
 
-    c.mark_complete(&0, &pr_url(&t.env));
-    c.approve(&0);
+#[test]
+#[should_panic(expected = "Error(Contract, #37)")]
+fn test_request_changes_round_limit() {
+    let t = setup();
+    do_initialize(&t, 0);
+    let c = client(&t);
 
-    let before = token_balance(&t.env, &t.token, &t.client);
-    c.cancel();
-    let after  = token_balance(&t.env, &t.token, &t.client);
-    assert_eq!(after - before, 700_0000000i128);
+    for _ in 0..3 {
+        c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+        c.request_changes(&0, &note(&t.env));
+    }
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.request_changes(&0, &note(&t.env));
+}
+
+// ─── Approval deadline ────────────────────────────────────────────────────────
+
+fn manual_config(timeout: ApprovalTimeout) -> EscrowConfig {
+    EscrowConfig {
+        approval_deadline_secs: 604800,
+        approval_timeout:       timeout,
+        ..default_config()
+    }
 }
 
 #[test]
-#[should_panic(expected = "Error(Contract, #51)")]
-fn test_cancel_blocked_pending() {
+fn test_approval_deadline_auto_release() {
     let t = setup();
-    do_initialize(&t, 259200);
+    do_initialize_with(&t, 0, manual_config(ApprovalTimeout::AutoRelease));
     let c = client(&t);
-    c.mark_complete(&0, &pr_url(&t.env));
-    c.cancel();
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    assert_eq!(c.time_until_auto_release(&0), 604800);
+
+    advance_time(&t.env, 604800);
+    c.auto_release(&0, &None);
+    assert_eq!(c.get_milestone(&0).status, MilestoneStatus::Released);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #43)")]
+fn test_approval_deadline_auto_release_too_early_fails() {
+    let t = setup();
+    do_initialize_with(&t, 0, manual_config(ApprovalTimeout::AutoRelease));
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    advance_time(&t.env, 604799);
+    c.auto_release(&0, &None);
+}
+
+#[test]
+fn test_approval_deadline_escalate_to_arbitration() {
+    let t = setup();
+    do_initialize_with(&t, 0, manual_config(ApprovalTimeout::Escalate));
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    advance_time(&t.env, 604800);
+
+    c.escalate(&0, &String::from_str(&t.env, "No response from client"));
+    assert_eq!(c.get_milestone(&0).status, MilestoneStatus::Disputed);
+
+    c.resolve_dispute(&t.backend, &0, &true);
+    assert_eq!(c.get_milestone(&0).status, MilestoneStatus::Released);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #50)")]
+fn test_approval_deadline_escalate_policy_blocks_auto_release() {
+    let t = setup();
+    do_initialize_with(&t, 0, manual_config(ApprovalTimeout::Escalate));
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    advance_time(&t.env, 604800);
+    c.auto_release(&0, &None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #44)")]
+fn test_escalate_with_dispute_window_fails() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    advance_time(&t.env, 259201);
+    c.escalate(&0, &String::from_str(&t.env, "Use auto_release instead"));
+}
+
+// ─── Keeper registry ──────────────────────────────────────────────────────────
+
+fn setup_registry(t: &TestEnv) -> KeeperRegistryClient<'_> {
+    let registry_id = t.env.register(KeeperRegistry, ());
+    let config = EscrowConfig { registry: Some(registry_id.clone()), ..default_config() };
+    do_initialize_with(t, 259200, config);
+    KeeperRegistryClient::new(&t.env, &registry_id)
+}
+
+#[test]
+fn test_registry_tracks_window_until_release() {
+    let t = setup();
+    let r = setup_registry(&t);
+    let c = client(&t);
+
+    let now = t.env.ledger().timestamp();
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    assert_eq!(r.get_deadline(&t.contract_id, &0), Some(now + 259200));
+    assert_eq!(r.due_before(&(now + 259200), &0, &10).len(), 2);
+
+    c.approve(&0);
+    advance_time(&t.env, 259201);
+    let due = r.due_before(&t.env.ledger().timestamp(), &0, &10);
+    assert_eq!(due.len(), 1);
+    assert_eq!(due.get(0).unwrap().milestone_index, 1);
+
+    c.auto_release(&1, &None);
+    assert_eq!(r.count(), 0);
+}
+
+#[test]
+fn test_registry_cleared_on_dispute() {
+    let t = setup();
+    let r = setup_registry(&t);
+    let c = client(&t);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.dispute(&0, &String::from_str(&t.env, "Broken build"));
+    assert_eq!(r.get_deadline(&t.contract_id, &0), None);
+}
+
+#[test]
+fn test_registry_spam_does_not_drop_deadlines() {
+    let t = setup();
+    let r = setup_registry(&t);
+    let c = client(&t);
+
+    // Someone else piles deadlines into the same second this escrow's lands in
+    let due_at  = t.env.ledger().timestamp() + 259200;
+    let spammer = Address::generate(&t.env);
+    for i in 0..MAX_ESCROW_DEADLINES {
+        r.register(&spammer, &i, &due_at);
+    }
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    assert_eq!(r.get_deadline(&t.contract_id, &0), Some(due_at));
+}
+
+#[test]
+fn test_failing_registry_never_blocks_release() {
+    let t = setup();
+    let missing = Address::generate(&t.env);
+    let config  = EscrowConfig { registry: Some(missing), ..default_config() };
+    do_initialize_with(&t, 259200, config);
+    let c = client(&t);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve(&0);
+    assert_eq!(c.get_milestone(&0).status, MilestoneStatus::Released);
+}
+
+// ─── Dispute ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_dispute_within_window() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+
+    c.dispute(&0, &String::from_str(&t.env, "Does not match spec"));
+    assert_eq!(c.get_milestone(&0).status, MilestoneStatus::Disputed);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #42)")]
+fn test_dispute_after_window_fails() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    advance_time(&t.env, 259201);
+    c.dispute(&0, &String::from_str(&t.env, "Too late"));
+}
+
+#[test]
+fn test_dispute_resolved_dev_wins() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.dispute(&0, &String::from_str(&t.env, "Dispute"));
+
+    let dev_before = token_balance(&t.env, &t.token, &t.developer);
+    c.resolve_dispute(&t.backend, &0, &true);
+    let dev_after  = token_balance(&t.env, &t.token, &t.developer);
+    assert!(dev_after > dev_before);
+}
+
+#[test]
+fn test_dispute_resolved_client_wins() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.dispute(&0, &String::from_str(&t.env, "Not delivered"));
+
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.resolve_dispute(&t.backend, &0, &false);
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+    assert_eq!(client_after - client_before, 300_0000000i128);
+}
+
+// ─── Batch operations ─────────────────────────────────────────────────────────
+
+fn completions(env: &Env, indices: &[u32]) -> Vec<(u32, String)> {
+    let mut v = Vec::new(env);
+    for i in indices {
+        v.push_back((*i, pr_url(env)));
+    }
+    v
+}
+
+fn indices(env: &Env, items: &[u32]) -> Vec<u32> {
+    let mut v = Vec::new(env);
+    for i in items {
+        v.push_back(*i);
+    }
+    v
+}
+
+#[test]
+fn test_mark_complete_many_reports_bad_index() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    let results = c.mark_complete_many(&t.backend, &completions(&t.env, &[0, 7, 2]), &false);
+
+    assert_eq!(results.get(0).unwrap(), BatchResult { milestone_index: 0, error: None });
+    assert_eq!(results.get(1).unwrap(), BatchResult { milestone_index: 7, error: Some(30) });
+    assert_eq!(results.get(2).unwrap(), BatchResult { milestone_index: 2, error: None });
+    assert_eq!(c.get_milestone(&0).status, MilestoneStatus::PendingRelease);
+    assert_eq!(c.get_milestone(&1).status, MilestoneStatus::Pending);
+    assert_eq!(c.get_milestone(&2).status, MilestoneStatus::PendingRelease);
+}
+
+#[test]
+fn test_approve_many_completes_escrow() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete_many(&t.backend, &completions(&t.env, &[0, 1, 2]), &true);
+
+    let dev_before = token_balance(&t.env, &t.token, &t.developer);
+    let results = c.approve_many(&indices(&t.env, &[0, 1, 2]), &true);
+    let dev_after  = token_balance(&t.env, &t.token, &t.developer);
+
+    assert!(results.iter().all(|r| r.error.is_none()));
+    assert_eq!(dev_after - dev_before, 1_000_0000000i128 * 99 / 100);
+    assert_eq!(token_balance(&t.env, &t.token, &t.backend), 10_0000000);
+    assert_eq!(c.get_status(), EscrowStatus::Completed);
+}
+
+#[test]
+fn test_approve_many_skips_unfinished() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+
+    let results = c.approve_many(&indices(&t.env, &[0, 1]), &false);
+
+    assert_eq!(results.get(0).unwrap().error, None);
+    assert_eq!(results.get(1).unwrap().error, Some(32));
+    assert_eq!(c.get_milestone(&0).status, MilestoneStatus::Released);
+    assert_eq!(c.get_status(), EscrowStatus::Active);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #32)")]
+fn test_approve_many_atomic_aborts() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve_many(&indices(&t.env, &[0, 1]), &true);
+}
+
+#[test]
+fn test_auto_release_all_only_expired() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    advance_time(&t.env, 259201);
+    c.mark_complete(&t.backend, &2, &pr_url(&t.env));
+
+    let dev_before = token_balance(&t.env, &t.token, &t.developer);
+    let results = c.auto_release_all(&None);
+    let dev_after  = token_balance(&t.env, &t.token, &t.developer);
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results.get(2).unwrap(), BatchResult { milestone_index: 2, error: Some(41) });
+    assert_eq!(dev_after - dev_before, 700_0000000i128 * 99 / 100);
+    assert_eq!(c.get_milestone(&2).status, MilestoneStatus::PendingRelease);
+}
+
+// ─── Change orders ────────────────────────────────────────────────────────────
+
+fn rebalance(env: &Env, updates: &[(u32, u32)]) -> Vec<BpsUpdate> {
+    let mut v = Vec::new(env);
+    for (milestone_index, bps) in updates {
+        v.push_back(BpsUpdate { milestone_index: *milestone_index, bps: *bps });
+    }
+    v
+}
+
+#[test]
+fn test_change_order_add_remove_rebalance() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve(&0);
+
+    // Drop Delivery, shrink Backend, add QA + Docs
+    let mut add = Vec::new(&t.env);
+    add.push_back(milestone(&t.env, "QA",   "feat/qa",   2500));
+    add.push_back(milestone(&t.env, "Docs", "feat/docs", 2000));
+    let order = ChangeOrder {
+        add,
+        remove:    indices(&t.env, &[2]),
+        rebalance: rebalance(&t.env, &[(1, 2500)]),
+    };
+
+    c.propose_change_order(&Party::Client, &order);
+    let version = c.accept_change_order(&0);
+
+    assert_eq!(version, 1);
+    assert_eq!(c.get_terms_version(), 1);
+    assert!(c.get_change_order().is_none());
+
+    let ms = c.get_milestones();
+    assert_eq!(ms.len(), 5);
+    assert_eq!(ms.get(0).unwrap().status, MilestoneStatus::Released);
+    assert_eq!(ms.get(1).unwrap().bps, 2500);
+    assert_eq!(ms.get(2).unwrap().status, MilestoneStatus::Removed);
+    assert_eq!(ms.get(2).unwrap().bps, 0);
+
+    // New milestone pays its share of the unchanged total
+    c.mark_complete(&t.backend, &3, &pr_url(&t.env));
+    let dev_before = token_balance(&t.env, &t.token, &t.developer);
+    c.approve(&3);
+    let dev_after  = token_balance(&t.env, &t.token, &t.developer);
+    assert_eq!(dev_after - dev_before, 250_0000000i128 * 99 / 100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #38)")]
+fn test_change_order_cannot_touch_in_flight() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+
+    let order = ChangeOrder {
+        add:       Vec::new(&t.env),
+        remove:    Vec::new(&t.env),
+        rebalance: rebalance(&t.env, &[(0, 2000), (1, 5000)]),
+    };
+    c.propose_change_order(&Party::Developer, &order);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #38)")]
+fn test_change_order_cannot_add_payee() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    let mut add = Vec::new(&t.env);
+    add.push_back(Milestone {
+        payee: Some(Address::generate(&t.env)),
+        ..milestone(&t.env, "Docs", "feat/docs", 3000)
+    });
+    let order = ChangeOrder {
+        add,
+        remove:    indices(&t.env, &[2]),
+        rebalance: Vec::new(&t.env),
+    };
+    c.propose_change_order(&Party::Developer, &order);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn test_change_order_must_keep_bps_sum() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    let order = ChangeOrder {
+        add:       Vec::new(&t.env),
+        remove:    indices(&t.env, &[2]),
+        rebalance: Vec::new(&t.env),
+    };
+    c.propose_change_order(&Party::Client, &order);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #38)")]
+fn test_change_order_revalidated_on_accept() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    let order = ChangeOrder {
+        add:       Vec::new(&t.env),
+        remove:    Vec::new(&t.env),
+        rebalance: rebalance(&t.env, &[(1, 3000), (2, 4000)]),
+    };
+    c.propose_change_order(&Party::Developer, &order);
+
+    // Work on milestone 2 lands before the client accepts
+    c.mark_complete(&t.backend, &2, &pr_url(&t.env));
+    c.accept_change_order(&0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #55)")]
+fn test_change_order_withdrawn() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    let order = ChangeOrder {
+        add:       Vec::new(&t.env),
+        remove:    Vec::new(&t.env),
+        rebalance: rebalance(&t.env, &[(1, 3000), (2, 4000)]),
+    };
+    c.propose_change_order(&Party::Developer, &order);
+    c.withdraw_change_order(&Party::Client);
+    c.accept_change_order(&0);
+}
+
+// ─── Expiry ───────────────────────────────────────────────────────────────────
+
+fn expiry_config(t: &TestEnv, dispute_default: Party) -> EscrowConfig {
+    EscrowConfig {
+        expires_at: t.env.ledger().timestamp() + 10 * 86400,
+        dispute_default,
+        ..default_config()
+    }
+}
+
+#[test]
+fn test_expire_settles_everything() {
+    let t = setup();
+    do_initialize_with(&t, 259200, expiry_config(&t, Party::Client));
+    let c = client(&t);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.dispute(&1, &String::from_str(&t.env, "Incomplete"));
+
+    advance_time(&t.env, 10 * 86400);
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.expire(&None);
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+
+    // Design released past its window; Backend's dispute and unstarted Delivery refunded
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 297_0000000);
+    assert_eq!(client_after - client_before, 700_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+    assert_eq!(c.get_status(), EscrowStatus::Expired);
+}
+
+#[test]
+fn test_expire_pays_keeper_on_releases() {
+    let t = setup();
+    do_initialize_with(&t, 259200, EscrowConfig {
+        keeper_bps: 50,
+        ..expiry_config(&t, Party::Client)
+    });
+    let c = client(&t);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    advance_time(&t.env, 10 * 86400);
+
+    let keeper = Address::generate(&t.env);
+    c.expire(&Some(keeper.clone()));
+
+    // Only Design is released: half of its 3 USDC fee goes to the keeper,
+    // the refunds carry none
+    assert_eq!(token_balance(&t.env, &t.token, &keeper), 1_5000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.backend), 1_5000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 297_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #23)")]
+fn test_no_completion_after_expiry() {
+    let t = setup();
+    do_initialize_with(&t, 259200, expiry_config(&t, Party::Client));
+    let c = client(&t);
+
+    // A fresh review window would otherwise keep expire() from ever running
+    advance_time(&t.env, 10 * 86400);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+}
+
+#[test]
+fn test_expire_dispute_default_developer() {
+    let t = setup();
+    do_initialize_with(&t, 259200, expiry_config(&t, Party::Developer));
+    let c = client(&t);
+
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.dispute(&1, &String::from_str(&t.env, "Incomplete"));
+
+    advance_time(&t.env, 10 * 86400);
+    c.expire(&None);
+
+    assert_eq!(c.get_milestone(&1).status, MilestoneStatus::Released);
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 396_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #90)")]
+fn test_expire_too_early_fails() {
+    let t = setup();
+    do_initialize_with(&t, 259200, expiry_config(&t, Party::Client));
+    client(&t).expire(&None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #41)")]
+fn test_expire_waits_for_open_window() {
+    let t = setup();
+    do_initialize_with(&t, 259200, expiry_config(&t, Party::Client));
+    let c = client(&t);
+
+    advance_time(&t.env, 10 * 86400 - 100);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    advance_time(&t.env, 100);
+    c.expire(&None);
+}
+
+// ─── Deadlines ────────────────────────────────────────────────────────────────
+
+/// Same milestones as make_milestones(), with Design due a day from now.
+fn do_initialize_with_deadline(t: &TestEnv, policy: LatePolicy) {
+    let mut ms = make_milestones(&t.env);
+    let design = ms.get(0).unwrap();
+    ms.set(0, Milestone { due_at: Some(t.env.ledger().timestamp() + 86400), ..design });
+
+    client(t).initialize(
+        &t.client, &t.developer, &t.backend,
+        &t.token, &1_000_0000000i128, &ms, &0,
+        &EscrowConfig { late_policy: policy, ..default_config() },
+        &metadata(&t.env),
+    );
+    accept_and_fund(t);
+}
+
+#[test]
+fn test_late_penalty_goes_to_client() {
+    let t = setup();
+    do_initialize_with_deadline(&t, LatePolicy::Penalty(500));
+    let c = client(&t);
+
+    // A day and a bit late = 2 started days = 10% off 300
+    advance_time(&t.env, 2 * 86400 + 60);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.approve(&0);
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+
+    assert_eq!(client_after - client_before, 30_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 267_3000000);
+}
+
+#[test]
+fn test_on_time_milestone_pays_in_full() {
+    let t = setup();
+    do_initialize_with_deadline(&t, LatePolicy::Penalty(500));
+    let c = client(&t);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    advance_time(&t.env, 5 * 86400);
+    c.approve(&0);
+
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 297_0000000);
+}
+
+#[test]
+fn test_reclaim_after_hard_deadline() {
+    let t = setup();
+    do_initialize_with_deadline(&t, LatePolicy::Reclaim(3600));
+    let c = client(&t);
+
+    advance_time(&t.env, 86400 + 3600);
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.reclaim_milestone(&0);
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+
+    assert_eq!(client_after - client_before, 300_0000000);
+    assert_eq!(c.get_milestone(&0).status, MilestoneStatus::Refunded);
+    assert_eq!(c.get_status(), EscrowStatus::Active);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #90)")]
+fn test_reclaim_before_hard_deadline_fails() {
+    let t = setup();
+    do_initialize_with_deadline(&t, LatePolicy::Reclaim(3600));
+    advance_time(&t.env, 86400);
+    client(&t).reclaim_milestone(&0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #91)")]
+fn test_reclaim_without_due_date_fails() {
+    let t = setup();
+    do_initialize_with_deadline(&t, LatePolicy::Reclaim(0));
+    advance_time(&t.env, 86400);
+    client(&t).reclaim_milestone(&1);
+}
+
+// ─── Retention / warranty ─────────────────────────────────────────────────────
+
+fn retention_config() -> EscrowConfig {
+    EscrowConfig { retention_bps: 1000, warranty_secs: 86400, ..default_config() }
+}
+
+fn approve_all(t: &TestEnv) {
+    let c = client(t);
+    for i in 0u32..3 {
+        c.mark_complete(&t.backend, &i, &pr_url(&t.env));
+        c.approve(&i);
+    }
+}
+
+#[test]
+fn test_retention_released_after_warranty() {
+    let t = setup();
+    do_initialize_with(&t, 0, retention_config());
+    let c = client(&t);
+    approve_all(&t);
+
+    // 10% held back on top of the 1% fee
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 890_0000000);
+    assert_eq!(c.get_status(), EscrowStatus::Warranty);
+    assert_eq!(c.get_retention().0, 100_0000000);
+
+    advance_time(&t.env, 86400);
+    c.release_retention();
+
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 990_0000000);
+    assert_eq!(c.get_status(), EscrowStatus::Completed);
+    assert_eq!(c.get_retention().0, 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #90)")]
+fn test_release_retention_during_warranty_fails() {
+    let t = setup();
+    do_initialize_with(&t, 0, retention_config());
+    approve_all(&t);
+    client(&t).release_retention();
+}
+
+#[test]
+fn test_warranty_claim_refunds_client() {
+    let t = setup();
+    do_initialize_with(&t, 0, retention_config());
+    let c = client(&t);
+    approve_all(&t);
+
+    c.claim_warranty(&String::from_str(&t.env, "Login broken in prod"));
+    assert!(c.get_warranty_claim().is_some());
+
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.resolve_warranty_claim(&t.backend, &false);
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+
+    assert_eq!(client_after - client_before, 100_0000000);
+    assert_eq!(c.get_status(), EscrowStatus::Completed);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #81)")]
+fn test_open_claim_blocks_retention_release() {
+    let t = setup();
+    do_initialize_with(&t, 0, retention_config());
+    let c = client(&t);
+    approve_all(&t);
+
+    c.claim_warranty(&String::from_str(&t.env, "Crash on upload"));
+    advance_time(&t.env, 86400);
+    c.release_retention();
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #80)")]
+fn test_warranty_claim_after_expiry_fails() {
+    let t = setup();
+    do_initialize_with(&t, 0, retention_config());
+    approve_all(&t);
+
+    advance_time(&t.env, 86400);
+    client(&t).claim_warranty(&String::from_str(&t.env, "Too late"));
+}
+
+// ─── Just-in-time funding ─────────────────────────────────────────────────────
+
+fn jit_config(funded_ahead: u32) -> EscrowConfig {
+    EscrowConfig { funding_mode: FundingMode::JustInTime, funded_ahead, ..default_config() }
+}
+
+#[test]
+fn test_jit_funds_first_milestones_only() {
+    let t = setup();
+    do_initialize_with(&t, 0, jit_config(1));
+    let c = client(&t);
+
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 300_0000000);
+
+    let coverage = c.get_funding();
+    assert_eq!(coverage.funded_amount, 300_0000000);
+    assert_eq!(coverage.unfunded_amount, 700_0000000);
+    assert_eq!(coverage.next_unfunded, Some(1));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #70)")]
+fn test_mark_complete_unfunded_fails() {
+    let t = setup();
+    do_initialize_with(&t, 0, jit_config(1));
+    client(&t).mark_complete(&t.backend, &1, &pr_url(&t.env));
+}
+
+#[test]
+fn test_fund_milestone_then_complete() {
+    let t = setup();
+    do_initialize_with(&t, 0, jit_config(0));
+    let c = client(&t);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+
+    c.fund_milestone(&1);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 400_0000000);
+    assert!(c.get_milestone(&1).funded);
+
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.approve(&1);
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 396_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #71)")]
+fn test_fund_milestone_twice_fails() {
+    let t = setup();
+    do_initialize(&t, 0);
+    client(&t).fund_milestone(&0);
+}
+
+#[test]
+fn test_approve_rolls_funding_forward() {
+    let t = setup();
+    do_initialize_with(&t, 0, jit_config(1));
+    let c = client(&t);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve(&0);
+
+    assert!(c.get_milestone(&1).funded);
+    assert!(!c.get_milestone(&2).funded);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 400_0000000);
+}
+
+#[test]
+fn test_jit_cancel_refunds_only_funded() {
+    let t = setup();
+    do_initialize_with(&t, 0, jit_config(1));
+    let c = client(&t);
+
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.cancel();
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+
+    assert_eq!(client_after - client_before, 300_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+}
+
+#[test]
+fn test_jit_change_order_refunds_repriced_funding() {
+    let t = setup();
+    do_initialize_with(&t, 0, jit_config(1));
+    let c = client(&t);
+
+    // Design 3000 → 2000, Delivery 3000 → 4000
+    let order = ChangeOrder {
+        add:       Vec::new(&t.env),
+        remove:    Vec::new(&t.env),
+        rebalance: rebalance(&t.env, &[(0, 2000), (2, 4000)]),
+    };
+    c.propose_change_order(&Party::Client, &order);
+    c.accept_change_order(&0);
+
+    assert!(!c.get_milestone(&0).funded);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+
+    c.fund_milestone(&0);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 200_0000000);
+}
+
+// ─── Renegotiate total ────────────────────────────────────────────────────────
+
+#[test]
+fn test_renegotiate_increase_rescales_remaining() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve(&0);
+
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.renegotiate_total(&1_700_0000000i128);
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+    assert_eq!(client_before - client_after, 700_0000000);
+
+    // 300 already released; 1400 left split 40:30 between Backend and Delivery
+    assert_eq!(c.get_milestone_amount(&0), 0);
+    assert_eq!(c.get_milestone_amount(&1), 800_0000000);
+    assert_eq!(c.get_milestone_amount(&2), 600_0000000);
+
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.approve(&1);
+    c.mark_complete(&t.backend, &2, &pr_url(&t.env));
+    c.approve(&2);
+
+    let expected = 1_700_0000000i128 - 1_700_0000000i128 / 100;
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), expected);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+    assert_eq!(c.get_status(), EscrowStatus::Completed);
+}
+
+#[test]
+fn test_renegotiate_decrease_refunds_client() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve(&0);
+
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.renegotiate_total(&650_0000000i128);
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+
+    assert_eq!(client_after - client_before, 350_0000000);
+    assert_eq!(c.get_milestone_amount(&1), 200_0000000);
+    assert_eq!(c.get_milestone_amount(&2), 150_0000000);
+    assert_eq!(c.get_terms_version(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_renegotiate_below_released_fails() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve(&0);
+    c.renegotiate_total(&300_0000000i128);
+}
+
+#[test]
+fn test_renegotiate_keeps_fixed_amounts() {
+    let t = setup();
+    let c = client(&t);
+
+    let mut ms = Vec::new(&t.env);
+    ms.push_back(fixed_milestone(&t.env, "Design", "feat/design", 125_0000000));
+    ms.push_back(milestone(&t.env, "Backend",  "feat/backend",  5000));
+    ms.push_back(milestone(&t.env, "Delivery", "feat/delivery", 5000));
+    c.initialize(&t.client, &t.developer, &t.backend,
+                 &t.token, &1_000_0000000i128, &ms, &259200u64, &default_config(), &metadata(&t.env));
+    accept_and_fund(&t);
+
+    c.renegotiate_total(&1_125_0000000i128);
+
+    assert_eq!(c.get_milestone_amount(&0), 125_0000000);
+    assert_eq!(c.get_milestone_amount(&1), 500_0000000);
+    assert_eq!(c.get_milestone_amount(&2), 500_0000000);
+}
+
+// ─── Cancel ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn test_cancel_full_refund() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    let before = token_balance(&t.env, &t.token, &t.client);
+    c.cancel();
+    let after  = token_balance(&t.env, &t.token, &t.client);
+
+    assert_eq!(after - before, 1_000_0000000i128);
+    assert_eq!(c.get_status(), EscrowStatus::Cancelled);
+}
+
+#[test]
+fn test_cancel_partial_after_one_release() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve(&0);
+
+    let before = token_balance(&t.env, &t.token, &t.client);
+    c.cancel();
+    let after  = token_balance(&t.env, &t.token, &t.client);
+    assert_eq!(after - before, 700_0000000i128);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #51)")]
+fn test_cancel_blocked_pending() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.cancel();
+}
+
+#[test]
+fn test_cancel_pays_kill_fee() {
+    let t = setup();
+    do_initialize_with(&t, 0, EscrowConfig { kill_fee_bps: 1000, ..default_config() });
+    let c = client(&t);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve(&0);
+
+    // 10% of the 700 still held; developer gets it less the 1% fee
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.cancel();
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+
+    assert_eq!(client_after - client_before, 630_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 297_0000000 + 69_3000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+}
+
+// ─── Developer withdraw ───────────────────────────────────────────────────────
+
+#[test]
+fn test_developer_withdraw_lets_reviews_finish() {
+    let t = setup();
+    do_initialize(&t, 0);
+    let c = client(&t);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.developer_withdraw();
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+
+    assert_eq!(client_after - client_before, 700_0000000);
+    assert_eq!(c.get_status(), EscrowStatus::Active);
+
+    c.approve(&0);
+    let (_, topics, _) = t.env.events().all().last().unwrap();
+    let name: Symbol = topics.get(0).unwrap().into_val(&t.env);
+    assert_eq!(name, Symbol::new(&t.env, "withdrawn"));
+
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 297_0000000);
+    assert_eq!(c.get_status(), EscrowStatus::Withdrawn);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #27)")]
+fn test_developer_withdraw_is_terminal() {
+    let t = setup();
+    do_initialize(&t, 0);
+    let c = client(&t);
+
+    c.developer_withdraw();
+    assert_eq!(c.get_status(), EscrowStatus::Withdrawn);
+    c.cancel();
+}
+
+// ─── Mutual termination ───────────────────────────────────────────────────────
+
+#[test]
+fn test_mutual_termination_splits_everything_held() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.dispute(&1, &String::from_str(&t.env, "Half done"));
+
+    c.propose_termination(&Party::Developer, &4000);
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.accept_termination(&4000);
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+
+    // 40% of 1000 to the developer less 1%, 60% back to the client
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 396_0000000);
+    assert_eq!(client_after - client_before, 600_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+    assert_eq!(c.get_status(), EscrowStatus::Terminated);
+    assert_eq!(c.get_milestone(&1).status, MilestoneStatus::Settled);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #55)")]
+fn test_withdrawn_termination_cannot_be_accepted() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    c.propose_termination(&Party::Client, &1000);
+    c.withdraw_termination(&Party::Client);
+    c.accept_termination(&1000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #56)")]
+fn test_termination_goes_stale_after_progress() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    // Offered before the work was delivered — can't be used to settle it at 20%
+    c.propose_termination(&Party::Developer, &2000);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.accept_termination(&2000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #100)")]
+fn test_termination_accepts_only_the_offered_split() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+
+    c.propose_termination(&Party::Client, &1000);
+    c.accept_termination(&5000);
+}
+
+// ─── Backend signers ──────────────────────────────────────────────────────────
+
+#[test]
+fn test_backend_key_rotation() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    let new_key = Address::generate(&t.env);
+
+    c.add_backend_signer(&new_key, &0);
+    c.add_backend_signer(&t.backend, &(t.env.ledger().timestamp() + 3600));
+
+    // Both keys work during the overlap
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.mark_complete(&new_key, &1, &pr_url(&t.env));
+
+    advance_time(&t.env, 3600);
+    assert!(c.try_mark_complete(&t.backend, &2, &pr_url(&t.env)).is_err());
+    c.mark_complete(&new_key, &2, &pr_url(&t.env));
+    assert_eq!(c.get_backend_signers().len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #60)")]
+fn test_removed_signer_rejected() {
+    let t = setup();
+    do_initialize_with(&t, 0, EscrowConfig { signer_admin: Some(t.backend.clone()), ..default_config() });
+    let c = client(&t);
+    let replica = Address::generate(&t.env);
+
+    c.add_backend_signer(&replica, &0);
+    c.remove_backend_signer(&replica);
+    c.mark_complete(&replica, &0, &pr_url(&t.env));
+}
+
+#[test]
+fn test_fee_recipient_rotates_with_backend_key() {
+    let t = setup();
+    do_initialize(&t, 0);
+    let c = client(&t);
+    let new_key  = Address::generate(&t.env);
+    let treasury = Address::generate(&t.env);
+
+    // Old backend key retired entirely — signing and fees move elsewhere
+    c.add_backend_signer(&new_key, &0);
+    c.remove_backend_signer(&t.backend);
+    c.set_fee_recipient(&t.backend, &treasury);
+    c.mark_complete(&new_key, &0, &pr_url(&t.env));
+    c.approve(&0);
+
+    assert_eq!(token_balance(&t.env, &t.token, &treasury), 3_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.backend), 0);
+}
+
+#[test]
+fn test_parties_replace_lost_signer_admin() {
+    let t = setup();
+    let admin = Address::generate(&t.env);
+    do_initialize_with(&t, 0, EscrowConfig { signer_admin: Some(admin), ..default_config() });
+    let c = client(&t);
+    let new_key = Address::generate(&t.env);
+
+    // Admin key lost — after 30 silent days client and developer take over
+    c.request_admin_removal();
+    advance_time(&t.env, 30 * 86400);
+    c.finalize_admin_removal();
+    assert_eq!(c.get_terms().config.signer_admin, None);
+
+    c.add_backend_signer(&new_key, &0);
+    let auths = t.env.auths();
+    assert_eq!(auths.len(), 2);
+    assert_eq!(auths[0].0, t.client);
+    assert_eq!(auths[1].0, t.developer);
+
+    c.mark_complete(&new_key, &0, &pr_url(&t.env));
+}
+
+#[test]
+fn test_parties_cannot_divert_platform_fee() {
+    let t = setup();
+    let admin = Address::generate(&t.env);
+    do_initialize_with(&t, 0, EscrowConfig { signer_admin: Some(admin.clone()), ..default_config() });
+    let c = client(&t);
+
+    c.request_admin_removal();
+    advance_time(&t.env, 30 * 86400);
+    c.finalize_admin_removal();
+
+    // Installing themselves as admin doesn't give them the fee
+    c.set_signer_admin(&Some(t.client.clone()));
+    assert!(c.try_set_fee_recipient(&t.client, &t.client).is_err());
+
+    // The platform's own admin still can
+    let treasury = Address::generate(&t.env);
+    c.set_fee_recipient(&admin, &treasury);
+    assert_eq!(c.get_terms().backend, treasury);
+}
+
+#[test]
+fn test_present_admin_keeps_signer_set() {
+    let t = setup();
+    let admin = Address::generate(&t.env);
+    do_initialize_with(&t, 0, EscrowConfig { signer_admin: Some(admin.clone()), ..default_config() });
+    let c = client(&t);
+
+    // Only the admin signs a handover
+    c.set_signer_admin(&Some(admin.clone()));
+    let auths = t.env.auths();
+    assert_eq!(auths.len(), 1);
+    assert_eq!(auths[0].0, admin);
+
+    // And any admin action cancels a removal request
+    c.request_admin_removal();
+    c.add_backend_signer(&Address::generate(&t.env), &0);
+    advance_time(&t.env, 30 * 86400);
+    assert!(c.try_finalize_admin_removal().is_err());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn test_last_permanent_signer_cannot_expire() {
+    let t = setup();
+    do_initialize(&t, 0);
+    let c = client(&t);
+
+    c.add_backend_signer(&t.backend, &(t.env.ledger().timestamp() + 3600));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn test_last_signer_cannot_be_removed() {
+    let t = setup();
+    do_initialize(&t, 0);
+    client(&t).remove_backend_signer(&t.backend);
+}
+
+// ─── Client transfer ──────────────────────────────────────────────────────────
+
+#[test]
+fn test_client_transfer_carries_open_dispute() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    let treasury = Address::generate(&t.env);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.dispute(&0, &String::from_str(&t.env, "Doesn't match spec"));
+
+    c.propose_client_transfer(&treasury);
+    assert_eq!(c.get_pending_client(), Some(treasury.clone()));
+    c.accept_client_transfer();
+    assert_eq!(c.get_terms().client, treasury);
+    assert_eq!(c.get_pending_client(), None);
+
+    // The dispute the old key raised refunds the new one
+    c.resolve_dispute(&t.backend, &0, &false);
+    c.cancel();
+
+    assert_eq!(token_balance(&t.env, &t.token, &treasury), 1000_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #55)")]
+fn test_client_transfer_needs_proposal() {
+    let t = setup();
+    do_initialize(&t, 0);
+    client(&t).accept_client_transfer();
+}
+
+// ─── Payout address ───────────────────────────────────────────────────────────
+
+#[test]
+fn test_payout_address_redirects_releases() {
+    let t = setup();
+    do_initialize(&t, 0);
+    let c = client(&t);
+    let cold_wallet = Address::generate(&t.env);
+
+    c.set_payout_address(&cold_wallet, &None);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve(&0);
+
+    assert_eq!(token_balance(&t.env, &t.token, &cold_wallet), 297_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 0);
+}
+
+#[test]
+fn test_payout_address_can_change_during_warranty() {
+    let t = setup();
+    do_initialize_with(&t, 0, retention_config());
+    let c = client(&t);
+    let cold_wallet = Address::generate(&t.env);
+    approve_all(&t);
+
+    c.set_payout_address(&cold_wallet, &None);
+    advance_time(&t.env, 86400);
+    c.release_retention();
+
+    assert_eq!(token_balance(&t.env, &t.token, &cold_wallet), 100_0000000);
+}
+
+#[test]
+fn test_payout_change_waits_for_delay() {
+    let t = setup();
+    do_initialize_with(&t, 0, EscrowConfig { payout_delay_secs: 86400, ..default_config() });
+    let c = client(&t);
+    let cold_wallet = Address::generate(&t.env);
+    let design_wallet = Address::generate(&t.env);
+
+    c.set_payout_address(&cold_wallet, &None);
+    c.set_payout_address(&design_wallet, &Some(0));
+    assert_eq!(c.get_payout_address(&Some(0)), t.developer);
+
+    // Still inside the delay — the old address gets paid
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.approve(&1);
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 396_0000000);
+
+    advance_time(&t.env, 86400);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve(&0);
+    c.mark_complete(&t.backend, &2, &pr_url(&t.env));
+    c.approve(&2);
+
+    assert_eq!(token_balance(&t.env, &t.token, &design_wallet), 297_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &cold_wallet), 297_0000000);
+}
+
+// ─── Replace developer ────────────────────────────────────────────────────────
+
+#[test]
+fn test_replacement_splits_work_between_developers() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    let new_developer = Address::generate(&t.env);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve(&0);
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+
+    c.propose_replacement(&new_developer);
+    c.accept_replacement();
+    assert!(c.get_replacement().is_some());
+    c.consent_replacement();
+    assert!(c.get_replacement().is_none());
+
+    // Milestone 1 was in review at handover — still owed to the original developer
+    c.approve(&1);
+    c.mark_complete(&t.backend, &2, &pr_url(&t.env));
+    c.approve(&2);
+
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 693_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &new_developer), 297_0000000);
+    assert_eq!(c.get_status(), EscrowStatus::Completed);
+}
+
+#[test]
+fn test_reworked_milestone_pays_new_developer() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    let new_developer = Address::generate(&t.env);
+
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.propose_replacement(&new_developer);
+    c.accept_replacement();
+    c.consent_replacement();
+
+    // Sent back for rework, so the new developer redoes it and gets paid
+    c.request_changes(&1, &note(&t.env));
+    assert_eq!(c.get_milestone(&1).payee, None);
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.approve(&1);
+
+    assert_eq!(token_balance(&t.env, &t.token, &new_developer), 396_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 0);
+}
+
+#[test]
+fn test_pinned_milestone_retention_goes_to_old_developer() {
+    let t = setup();
+    do_initialize_with(&t, 259200, retention_config());
+    let c = client(&t);
+    let new_developer = Address::generate(&t.env);
+
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.propose_replacement(&new_developer);
+    c.accept_replacement();
+    c.consent_replacement();
+
+    c.approve(&1);
+    for i in [0u32, 2] {
+        c.mark_complete(&t.backend, &i, &pr_url(&t.env));
+        c.approve(&i);
+    }
+    advance_time(&t.env, 86400);
+    c.release_retention();
+
+    // Milestone 1 paid in full (less fee) to the original developer, no retention
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 396_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &new_developer), 594_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+}
+
+#[test]
+fn test_termination_after_replacement_pays_pinned_work() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    let new_developer = Address::generate(&t.env);
+
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.propose_replacement(&new_developer);
+    c.accept_replacement();
+    c.consent_replacement();
+
+    c.propose_termination(&Party::Client, &10000);
+    c.accept_termination(&10000);
+
+    // Milestone 1 was in review at handover — the split covers only the rest
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 396_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &new_developer), 594_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.contract_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #31)")]
+fn test_pinned_milestone_cannot_be_retracted() {
+    let t = setup();
+    do_initialize(&t, 259200);
+    let c = client(&t);
+    let new_developer = Address::generate(&t.env);
+
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.propose_replacement(&new_developer);
+    c.accept_replacement();
+    c.consent_replacement();
+
+    // The new developer can't co-sign away what the old one is owed
+    c.retract_completion(&t.backend, &1, &new_developer);
+}
+
+#[test]
+fn test_replacement_after_timeout_without_consent() {
+    let t = setup();
+    do_initialize_with(&t, 0, EscrowConfig { replacement_timeout_secs: 86400, ..default_config() });
+    let c = client(&t);
+    let new_developer = Address::generate(&t.env);
+
+    c.propose_replacement(&new_developer);
+    c.accept_replacement();
+    advance_time(&t.env, 86400);
+    c.finalize_replacement();
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.approve(&0);
+    assert_eq!(token_balance(&t.env, &t.token, &new_developer), 297_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #90)")]
+fn test_replacement_needs_consent_before_timeout() {
+    let t = setup();
+    do_initialize_with(&t, 0, EscrowConfig { replacement_timeout_secs: 86400, ..default_config() });
+    let c = client(&t);
+
+    c.propose_replacement(&Address::generate(&t.env));
+    c.accept_replacement();
+    advance_time(&t.env, 3600);
+    c.finalize_replacement();
+}
+
+// ─── Wind-down cancel ─────────────────────────────────────────────────────────
+
+fn notice_config() -> EscrowConfig {
+    EscrowConfig { cancel_notice_secs: 7 * 86400, ..default_config() }
+}
+
+#[test]
+fn test_wind_down_lets_work_finish() {
+    let t = setup();
+    do_initialize_with(&t, 0, notice_config());
+    let c = client(&t);
+
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.request_cancel();
+
+    // Work in progress still counts during the notice period
+    advance_time(&t.env, 86400);
+    c.mark_complete(&t.backend, &1, &pr_url(&t.env));
+    c.approve(&0);
+    c.approve(&1);
+
+    advance_time(&t.env, 6 * 86400);
+    let client_before = token_balance(&t.env, &t.token, &t.client);
+    c.finalize_cancel();
+    let client_after  = token_balance(&t.env, &t.token, &t.client);
+
+    assert_eq!(client_after - client_before, 300_0000000);
+    assert_eq!(token_balance(&t.env, &t.token, &t.developer), 693_0000000);
+    assert_eq!(c.get_status(), EscrowStatus::Cancelled);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #90)")]
+fn test_finalize_cancel_during_notice_fails() {
+    let t = setup();
+    do_initialize_with(&t, 0, notice_config());
+    let c = client(&t);
+
+    c.request_cancel();
+    advance_time(&t.env, 86400);
+    c.finalize_cancel();
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #26)")]
+fn test_cancel_refused_during_notice() {
+    let t = setup();
+    do_initialize_with(&t, 0, notice_config());
+    let c = client(&t);
+
+    c.request_cancel();
+    c.cancel();
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #21)")]
+fn test_no_completion_after_notice() {
+    let t = setup();
+    do_initialize_with(&t, 0, notice_config());
+    let c = client(&t);
+
+    c.request_cancel();
+    advance_time(&t.env, 7 * 86400);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #51)")]
+fn test_finalize_cancel_waits_for_reviews() {
+    let t = setup();
+    do_initialize_with(&t, 259200, notice_config());
+    let c = client(&t);
+
+    c.request_cancel();
+    advance_time(&t.env, 7 * 86400 - 100);
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    advance_time(&t.env, 100);
+    c.finalize_cancel();
 }
 
 // ─── Time remaining ───────────────────────────────────────────────────────────
@@ -305,7 +2204,7 @@ fn test_time_until_auto_release() {
     let t = setup();
     do_initialize(&t, 259200);
     let c = client(&t);
-    c.mark_complete(&0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
 
     let t1 = c.time_until_auto_release(&0);
     assert!(t1 > 0 && t1 <= 259200);
@@ -327,8 +2226,8 @@ fn test_mark_complete_twice_fails() {
     let t = setup();
     do_initialize(&t, 259200);
     let c = client(&t);
-    c.mark_complete(&0, &pr_url(&t.env));
-    c.mark_complete(&0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
 }
 
 #[test]
@@ -338,7 +2237,7 @@ fn test_ops_fail_after_cancel() {
     do_initialize(&t, 259200);
     let c = client(&t);
     c.cancel();
-    c.mark_complete(&0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
 }
 
 #[test]
@@ -349,9 +2248,9 @@ fn test_ops_fail_after_completion() {
     let c = client(&t);
 
     for i in 0u32..3 {
-        c.mark_complete(&i, &pr_url(&t.env));
+        c.mark_complete(&t.backend, &i, &pr_url(&t.env));
         c.approve(&i);
     }
     assert_eq!(c.get_status(), EscrowStatus::Completed);
-    c.mark_complete(&0, &pr_url(&t.env));
+    c.mark_complete(&t.backend, &0, &pr_url(&t.env));
 }
\ No newline at end of file
@@ -3,16 +3,21 @@
 // In Soroban, types stored on-chain must derive contracttype.
 // This makes them serializable to XDR for ledger storage.
 
-use soroban_sdk::{contracttype, String};
+use soroban_sdk::{contracttype, Address, Bytes, BytesN, String, Vec};
 
 // ─── Escrow status ────────────────────────────────────────────────────────────
 
 #[contracttype]
 #[derive(Clone, Debug, PartialEq)]
 pub enum EscrowStatus {
+    Draft,       // Terms proposed — waiting for the developer to accept and the client to fund
     Active,      // Escrow is live — milestones can be completed
     Completed,   // All milestones resolved — contract is done
     Cancelled,   // Client cancelled — remaining funds refunded
+    Warranty,    // All milestones resolved — retention held until warranty ends
+    Expired,     // expires_at passed — expire() settled whatever was left
+    Withdrawn,   // Developer stepped away — open work refunded, reviews settled
+    Terminated,  // Both parties agreed to close with a negotiated split
 }
 
 // ─── Milestone status ─────────────────────────────────────────────────────────
@@ -25,14 +30,17 @@ pub enum MilestoneStatus {
     Released,        // Funds sent to developer ✅
     Disputed,        // Client raised a dispute — funds frozen
     Refunded,        // Funds returned to client (dispute resolved in client's favor)
+    Removed,         // Dropped by a change order before work started — bps is 0
+    Settled,         // Closed by a mutual termination split
 }
 
 // ─── Milestone ────────────────────────────────────────────────────────────────
 
-/// A single unit of work with a proportional payout.
+/// A single unit of work with a proportional or fixed payout.
 ///
 /// `bps` (basis points) defines what % of total_amount this milestone pays.
 /// All milestone bps must sum to exactly 10_000 (= 100%).
+/// Alternatively `amount` pins an exact payout; bps milestones share the rest.
 ///
 /// Example for a $1000 escrow:
 ///   Milestone 1: title="Design",   bps=2000  → $200
@@ -48,10 +56,20 @@ pub struct Milestone {
     /// e.g. "feat/design" or "milestone-1"
     pub trigger_keyword: String,
 
-    /// Basis points — this milestone's share of total_amount
-    /// Must be > 0. All milestones must sum to 10_000.
+    /// Basis points — this milestone's share of total_amount (after fixed
+    /// amounts). 0 for fixed-amount milestones. Non-zero bps must sum to 10_000.
     pub bps: u32,
 
+    /// Exact payout in stroops. Pass it for a fixed-amount milestone (with
+    /// bps = 0), or 0 for a bps milestone — initialize() fills it in.
+    /// Release, refund and cancel always pay this stored amount.
+    pub amount: i128,
+
+    /// Whether `amount` is sitting in the contract. Always true in upfront
+    /// mode; in just-in-time mode set by fund_milestone() / funded_ahead.
+    /// Set by the contract — the value passed to initialize() is ignored.
+    pub funded: bool,
+
     /// Current status
     pub status: MilestoneStatus,
 
@@ -60,4 +78,294 @@ pub struct Milestone {
 
     /// Ledger timestamp when mark_complete() was called
     pub completed_at: Option<u64>,
+
+    /// Rework rounds the client has asked for via request_changes().
+    /// Capped at MAX_CHANGE_REQUESTS — pass 0 at initialize.
+    pub change_requests: u32,
+
+    /// Optional due date (ledger timestamp). What lateness costs is set by
+    /// EscrowConfig::late_policy.
+    pub due_at: Option<u64>,
+
+    /// Who this milestone pays instead of the current developer. Set by the
+    /// contract when a developer is replaced while it was in review, and
+    /// cleared if the client sends it back with request_changes() — pass None.
+    pub payee: Option<Address>,
+}
+
+// ─── Approval timeout ─────────────────────────────────────────────────────────
+
+/// What happens when a manual-approval escrow (dispute_window == 0) passes
+/// its approval deadline without the client calling approve().
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ApprovalTimeout {
+    Escalate,     // Developer may escalate() — milestone goes to arbitration
+    AutoRelease,  // auto_release() works, exactly like an expired dispute window
+}
+
+// ─── Late policy ──────────────────────────────────────────────────────────────
+
+/// What a milestone missing its `due_at` costs the developer.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum LatePolicy {
+    Disabled,      // due_at is informational only
+    Penalty(u32),  // Payout cut by this many bps per started day late — cut goes to the client
+    Reclaim(u64),  // This many seconds after due_at the client may reclaim_milestone()
+}
+
+// ─── Funding mode ─────────────────────────────────────────────────────────────
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum FundingMode {
+    Upfront,     // fund() pulls total_amount at once
+    JustInTime,  // Each milestone is funded separately before work is accepted
+}
+
+// ─── Escrow config ────────────────────────────────────────────────────────────
+
+/// Optional escrow terms, proposed in initialize() / counter_propose().
+///
+/// Zero values turn the corresponding feature off.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EscrowConfig {
+    /// Reward paid to whoever triggers a deadline-driven release
+    /// (auto_release, auto_release_all, expire), in bps of the released
+    /// amount. Taken from the 1% platform fee — capped at 50 (0.5%). Other
+    /// permissionless deadline calls (release_retention, finalize_cancel,
+    /// finalize_replacement) pay no reward.
+    pub keeper_bps: u32,
+
+    /// KeeperRegistry to push milestone deadlines to (None = don't publish).
+    pub registry: Option<Address>,
+
+    /// Who manages the backend signer set. None = client and developer,
+    /// signing together.
+    pub signer_admin: Option<Address>,
+
+    /// Manual-approval escrows only: seconds the client has to approve()
+    /// after mark_complete() before `approval_timeout` kicks in.
+    /// 0 = no deadline (client approval is the only way out).
+    pub approval_deadline_secs: u64,
+
+    /// Outcome once approval_deadline_secs has passed.
+    pub approval_timeout: ApprovalTimeout,
+
+    /// Mobilization advance paid to the developer inside fund(), as a
+    /// share of total_amount. Set at most one of the two; both 0 = no advance.
+    /// Milestones split what's left after it.
+    pub advance_bps: u32,
+    pub advance_amount: i128,
+
+    /// Share of every release held back in the contract as retention, paid
+    /// out by release_retention() once the escrow completes and
+    /// `warranty_secs` pass without a warranty claim. 0 = no retention.
+    pub retention_bps: u32,
+    pub warranty_secs: u64,
+
+    /// Consequence of missing a milestone's due_at.
+    pub late_policy: LatePolicy,
+
+    /// Ledger timestamp after which anyone may expire() the escrow. 0 = never.
+    pub expires_at: u64,
+
+    /// Who wins disputes still open when the escrow expires.
+    pub dispute_default: Party,
+
+    /// Kill fee paid to the developer when the client cancels, in bps of what
+    /// is still held. The platform fee applies to it. 0 = none.
+    pub kill_fee_bps: u32,
+
+    /// Notice period between request_cancel() and finalize_cancel().
+    pub cancel_notice_secs: u64,
+
+    /// Delay before a set_payout_address() change takes effect, so the
+    /// client can react to a hijacked developer key. 0 = immediate.
+    pub payout_delay_secs: u64,
+
+    /// How long the outgoing developer has to consent to a replacement
+    /// before finalize_replacement() works without them. 0 = consent required.
+    pub replacement_timeout_secs: u64,
+
+    /// How long a terms proposal (initialize() or counter_propose()) stays
+    /// open for acceptance. 0 = until withdrawn.
+    pub proposal_ttl_secs: u64,
+
+    /// Upfront (default) or just-in-time funding.
+    pub funding_mode: FundingMode,
+
+    /// Just-in-time only: keep this many open milestones funded. Funded by
+    /// fund() and topped up on every approve(). 0 = only fund_milestone().
+    pub funded_ahead: u32,
+}
+
+// ─── Parties ──────────────────────────────────────────────────────────────────
+
+/// The two sides of the deal, for flows where either may act
+/// (e.g. one proposes a change order, the other accepts).
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Party {
+    Client,
+    Developer,
+}
+
+// ─── Change orders ────────────────────────────────────────────────────────────
+
+/// New bps for an existing Pending milestone.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BpsUpdate {
+    pub milestone_index: u32,
+    pub bps: u32,
+}
+
+/// A scope change agreed between client and developer.
+///
+/// Applied in order: remove, rebalance, add. Only Pending milestones can be
+/// removed or rebalanced; added milestones are appended and must be fresh
+/// (Pending, no pr_url / completed_at). Afterwards bps must sum to 10_000.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct ChangeOrder {
+    pub add: Vec<Milestone>,
+    pub remove: Vec<u32>,
+    pub rebalance: Vec<BpsUpdate>,
+}
+
+/// A proposed change order waiting for the other party.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PendingChangeOrder {
+    pub proposer: Party,
+    /// Terms version the order was written against
+    pub base_version: u32,
+    pub order: ChangeOrder,
+}
+
+// ─── Metadata ─────────────────────────────────────────────────────────────────
+
+/// Off-chain references for an escrow. Set at initialize() and never changed.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct EscrowMetadata {
+    /// Backend's id for this escrow (e.g. escrows.id). Second topic of every
+    /// event. At most MAX_EXTERNAL_ID_LEN bytes.
+    pub external_id: Bytes,
+    /// sha256 of the signed statement-of-work document
+    pub terms_doc_hash: BytesN<32>,
+    /// Human-readable project title
+    pub title: String,
+    /// Repository reference, e.g. "acme/api"
+    pub repo: String,
+}
+
+// ─── Terms ────────────────────────────────────────────────────────────────────
+
+/// Everything both parties sign off on before funds are locked. The terms
+/// hash is sha256 over this struct's XDR.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct Terms {
+    pub client: Address,
+    pub developer: Address,
+    pub backend: Address,
+    pub token: Address,
+    pub total_amount: i128,
+    pub milestones: Vec<Milestone>,
+    pub dispute_window_secs: u64,
+    pub config: EscrowConfig,
+    pub metadata: EscrowMetadata,
+}
+
+/// The open proposal on a Draft escrow.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TermsProposal {
+    pub proposer: Party,
+    pub terms_hash: BytesN<32>,
+    /// 0 = no expiry
+    pub expires_at: u64,
+    /// Developer has accept_terms()'d a client proposal — client can fund()
+    pub accepted: bool,
+}
+
+// ─── Warranty claim ───────────────────────────────────────────────────────────
+
+/// Client's claim against the retention, raised during the warranty period.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WarrantyClaim {
+    pub reason: String,
+    pub raised_at: u64,
+}
+
+// ─── Funding coverage ─────────────────────────────────────────────────────────
+
+/// How much of the open (unsettled) work is backed by deposited funds.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FundingCoverage {
+    pub funded_amount: i128,
+    pub unfunded_amount: i128,
+    pub funded_milestones: u32,
+    pub unfunded_milestones: u32,
+    /// Lowest-index Pending milestone still waiting for funds
+    pub next_unfunded: Option<u32>,
+}
+
+// ─── Payout address ───────────────────────────────────────────────────────────
+
+/// A developer-requested payout address, in force from `effective_at`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PayoutChange {
+    pub address: Address,
+    pub effective_at: u64,
+}
+
+// ─── Developer replacement ────────────────────────────────────────────────────
+
+/// A client's proposal to hand the remaining work to a new developer.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingReplacement {
+    pub new_developer: Address,
+    pub proposed_at: u64,
+    /// New developer has accept_replacement()'d
+    pub accepted: bool,
+    /// Current developer has consent_replacement()'d
+    pub consented: bool,
+}
+
+// ─── Termination ──────────────────────────────────────────────────────────────
+
+/// A proposed mutual termination, waiting for the other party.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingTermination {
+    pub proposer: Party,
+    /// Developer's share of the held funds; the client gets the rest
+    pub developer_bps: u32,
+    /// Terms version the split was proposed against
+    pub base_version: u32,
+    /// sha256 of the milestones' XDR when proposed — any progress since
+    /// (completion, release, dispute) makes the proposal stale
+    pub milestones_hash: BytesN<32>,
+}
+
+// ─── Batch result ─────────────────────────────────────────────────────────────
+
+/// Outcome of one entry in a batch call (approve_many, auto_release_all, ...).
+///
+/// `error` is None on success, otherwise the contract Error code
+/// (e.g. Some(41) = DisputeWindowOpen).
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchResult {
+    pub milestone_index: u32,
+    pub error: Option<u32>,
 }